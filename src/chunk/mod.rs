@@ -0,0 +1,23 @@
+use crate::tree::Tree;
+
+pub mod regexp;
+pub mod util;
+
+/// Result of chunking a tagged sentence: a tree with (token, tag) pairs as leaves, grouped into labeled chunks
+pub type ChunkTree<'a> = Tree<(&'a str, &'a str)>;
+
+/// A chunk parser groups the tokens of a tagged sentence into (non-overlapping) chunks, like noun phrases
+pub trait ChunkParser {
+    /// Chunks (token, tag) pairs. The result has a root with the chunks and the unchunked tokens as children
+    fn parse<'a>(&self, sentence: &[(&'a str, &'a str)]) -> ChunkTree<'a>;
+
+    /// Chunks the tagged tokens of every gold tree, and scores the result against the chunks in the gold trees
+    fn evaluate(&self, gold: &[ChunkTree]) -> util::ChunkScore {
+        let mut score = util::ChunkScore::new();
+        for tree in gold {
+            let sentence: Vec<(&str, &str)> = tree.leaves().into_iter().copied().collect();
+            score.score(tree, &self.parse(&sentence));
+        }
+        score
+    }
+}
@@ -0,0 +1,352 @@
+use anyhow::anyhow;
+use regex::Regex;
+
+use crate::chunk::{ChunkParser, ChunkTree};
+use crate::tree::Child;
+
+/// a token or a chunk, while chunking
+type Node<'a> = Child<(&'a str, &'a str)>;
+
+/// Chunk parser with a grammar of regular expressions over part of speech tags.
+///
+/// The grammar has one or more stages, each with a label and a list of rules. A stage starts with `LABEL:`,
+/// followed by rules on the same or the next lines:
+/// * `{<DT>?<JJ>*<NN.*>}` chunk rule: chunks sequences of tags that match the pattern
+/// * `}<VBD|IN>+{` chink rule: removes sequences of tags that match the pattern from chunks
+///
+/// A tag pattern is a sequence of tags in angle brackets, each of which is a regular expression for a tag
+/// (where `.` does not cross tag boundaries), with quantifiers and groups in between. Text after a `#` outside a tag
+/// is a comment. A backslash escapes `#` and `:`, so `\#` is not a comment and `\:` in a label is not its end.
+///
+/// Stages are applied in order. The chunks of a stage take part in later stages as if they were a token with their label
+/// as tag, so that cascaded grammars like `PP: {<IN><NP>}` work.
+///
+/// ```
+/// use rltk::chunk::ChunkParser;
+/// use rltk::chunk::regexp::RegexpParser;
+///
+/// let parser = RegexpParser::new("NP: {<DT>?<JJ>*<NN>}").unwrap();
+/// let sentence = [("the", "DT"), ("little", "JJ"), ("dog", "NN"), ("barked", "VBD")];
+/// let tree = parser.parse(&sentence);
+/// assert_eq!(tree.to_string(), "(S (NP the/DT little/JJ dog/NN) barked/VBD)");
+/// ```
+pub struct RegexpParser {
+    stages: Vec<Stage>,
+    root_label: String,
+}
+
+struct Stage {
+    label: String,
+    rules: Vec<Rule>,
+}
+
+enum Rule {
+    Chunk(Regex),
+    Chink(Regex),
+}
+
+impl RegexpParser {
+    /// Parser with root label `S`
+    pub fn new(grammar: &str) -> anyhow::Result<Self> {
+        Self::with_root_label(grammar, "S")
+    }
+
+    pub fn with_root_label(grammar: &str, root_label: &str) -> anyhow::Result<Self> {
+        let mut stages: Vec<Stage> = Vec::new();
+        for line in grammar.lines() {
+            let mut line = strip_comment(line).trim();
+            if let Some((label, rules)) = split_label(line) {
+                stages.push(Stage { label: label.trim().replace("\\:", ":"), rules: Vec::new() });
+                line = rules.trim();
+            }
+            if line.is_empty() {
+                continue;
+            }
+            let stage = stages.last_mut().ok_or_else(|| anyhow!("rule without a label: {}", line))?;
+            stage.rules.push(Rule::parse(line)?);
+        }
+        if stages.is_empty() {
+            return Err(anyhow!("empty grammar"));
+        }
+        Ok(Self { stages, root_label: root_label.to_string() })
+    }
+}
+
+impl ChunkParser for RegexpParser {
+    /// Chunks a tagged sentence. The result is a chunk with the root label, containing the chunks and unchunked tokens.
+    fn parse<'a>(&self, sentence: &[(&'a str, &'a str)]) -> ChunkTree<'a> {
+        let mut nodes: Vec<Node<'a>> = sentence.iter().map(|&leaf| Child::Leaf(leaf)).collect();
+        for stage in &self.stages {
+            nodes = stage.apply(nodes);
+        }
+        ChunkTree::new(&self.root_label, nodes)
+    }
+}
+
+/// the line up to the first `#` that is not escaped or within a tag
+fn strip_comment(line: &str) -> &str {
+    let mut escaped = false;
+    let mut in_tag = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            '#' if !in_tag => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Splits `LABEL: rules` on the first `:` that is not escaped, if it comes before the first rule
+fn split_label(line: &str) -> Option<(&str, &str)> {
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' | '}' => return None,
+            ':' => return Some((&line[..index], &line[index + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+impl Rule {
+    fn parse(rule: &str) -> anyhow::Result<Self> {
+        if rule.len() >= 2 && rule.starts_with('{') && rule.ends_with('}') {
+            Ok(Rule::Chunk(tag_pattern_to_regex(&rule[1..rule.len() - 1])?))
+        } else if rule.len() >= 2 && rule.starts_with('}') && rule.ends_with('{') {
+            Ok(Rule::Chink(tag_pattern_to_regex(&rule[1..rule.len() - 1])?))
+        } else {
+            Err(anyhow!("invalid chunk rule: {}", rule))
+        }
+    }
+}
+
+/// Turns a tag pattern like `<DT>?<NN.*>+` into a regular expression over a string of tags like `<DT><NN><NNS>`
+fn tag_pattern_to_regex(pattern: &str) -> anyhow::Result<Regex> {
+    let pattern: String = pattern.chars().filter(|c| !c.is_whitespace()).collect();
+    if pattern.is_empty() || pattern.contains(['{', '}']) {
+        return Err(anyhow!("invalid tag pattern: {}", pattern));
+    }
+    let mut regex = String::new();
+    let mut escaped = false;
+    let mut in_tag = false;
+    for c in pattern.chars() {
+        match c {
+            // classes like `\d` stay as they are, other characters are matched literally
+            _ if escaped => {
+                if c.is_ascii_alphanumeric() {
+                    regex.push('\\');
+                    regex.push(c);
+                } else {
+                    regex.push_str(&regex::escape(&c.to_string()));
+                }
+                escaped = false;
+            }
+            '\\' => escaped = true,
+            '<' => {
+                regex.push_str("(?:<(?:");
+                in_tag = true;
+            }
+            '>' => {
+                regex.push_str(")>)");
+                in_tag = false;
+            }
+            // within a tag, `.` should not match the brackets around tags
+            '.' if in_tag => regex.push_str("[^<>]"),
+            _ => regex.push(c),
+        }
+    }
+    Ok(Regex::new(&regex)?)
+}
+
+impl Stage {
+    fn apply<'a>(&self, nodes: Vec<Node<'a>>) -> Vec<Node<'a>> {
+        // chunks from earlier stages take part with their label as tag
+        let tags: Vec<String> = nodes.iter()
+            .map(|node| match node {
+                Child::Leaf((_, tag)) => tag.to_string(),
+                Child::Tree(chunk) => chunk.label.clone(),
+            })
+            .collect();
+        // chunk membership per node, 0 is outside of any chunk
+        let mut chunk_ids = vec![0; nodes.len()];
+        let mut next_id = 1;
+        for rule in &self.rules {
+            match rule {
+                Rule::Chunk(regex) => {
+                    for (start, end) in runs(&chunk_ids, |id| id == 0) {
+                        for (match_start, match_end) in matches(regex, &tags[start..end]) {
+                            chunk_ids[start + match_start..start + match_end].fill(next_id);
+                            next_id += 1;
+                        }
+                    }
+                }
+                Rule::Chink(regex) => {
+                    for (start, end) in runs(&chunk_ids, |id| id != 0) {
+                        for (match_start, match_end) in matches(regex, &tags[start..end]) {
+                            chunk_ids[start + match_start..start + match_end].fill(0);
+                        }
+                        // the remainder after a chink is a new chunk
+                        let mut previous = 0;
+                        for id in chunk_ids[start..end].iter_mut() {
+                            if *id == 0 && previous != 0 {
+                                next_id += 1;
+                            }
+                            if *id != 0 {
+                                previous = *id;
+                                *id = next_id;
+                            } else {
+                                previous = 0;
+                            }
+                        }
+                        next_id += 1;
+                    }
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        let mut current: Option<(usize, Vec<Node<'a>>)> = None;
+        for (node, id) in nodes.into_iter().zip(chunk_ids) {
+            if let Some((current_id, children)) = current.take() {
+                if current_id == id {
+                    current = Some((current_id, children));
+                } else {
+                    result.push(Child::Tree(ChunkTree::new(&self.label, children)));
+                }
+            }
+            if id == 0 {
+                result.push(node);
+            } else {
+                current.get_or_insert_with(|| (id, Vec::new())).1.push(node);
+            }
+        }
+        if let Some((_, children)) = current {
+            result.push(Child::Tree(ChunkTree::new(&self.label, children)));
+        }
+        result
+    }
+}
+
+/// the maximal ranges of consecutive nodes whose chunk id satisfies the predicate,
+/// where every chunk is a range of its own
+fn runs(chunk_ids: &[usize], predicate: impl Fn(usize) -> bool) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    for i in 0..=chunk_ids.len() {
+        let continues = i < chunk_ids.len() && predicate(chunk_ids[i])
+            && start.is_none_or(|s: usize| chunk_ids[s] == chunk_ids[i]);
+        if !continues {
+            if let Some(s) = start.take() {
+                runs.push((s, i));
+            }
+            if i < chunk_ids.len() && predicate(chunk_ids[i]) {
+                start = Some(i);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    runs
+}
+
+/// non-empty matches of the regex on the tags, as ranges of tag indices
+fn matches(regex: &Regex, tags: &[String]) -> Vec<(usize, usize)> {
+    let mut text = String::new();
+    // tag index at every byte offset where a tag starts
+    let mut offsets = Vec::with_capacity(tags.len() + 1);
+    for tag in tags {
+        offsets.push(text.len());
+        text.push('<');
+        text.push_str(tag);
+        text.push('>');
+    }
+    offsets.push(text.len());
+
+    regex.find_iter(&text)
+        .filter(|m| !m.as_str().is_empty())
+        .filter_map(|m| {
+            let start = offsets.binary_search(&m.start()).ok()?;
+            let end = offsets.binary_search(&m.end()).ok()?;
+            Some((start, end))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence() -> Vec<(&'static str, &'static str)> {
+        vec![("the", "DT"), ("little", "JJ"), ("yellow", "JJ"), ("dog", "NN"), ("barked", "VBD"), ("at", "IN"),
+            ("the", "DT"), ("cat", "NN")]
+    }
+
+    #[test]
+    fn test_chunk() {
+        let parser = RegexpParser::new("NP: {<DT>?<JJ>*<NN>}").unwrap();
+        let tree = parser.parse(&sentence());
+        assert_eq!(tree.to_string(), "(S (NP the/DT little/JJ yellow/JJ dog/NN) barked/VBD at/IN (NP the/DT cat/NN))");
+        assert_eq!(tree.subtrees(|chunk| chunk.label == "NP").len(), 2);
+        assert_eq!(tree.leaves().into_iter().copied().collect::<Vec<_>>(), sentence());
+    }
+
+    #[test]
+    fn test_dot_stays_within_tag() {
+        let parser = RegexpParser::new("NP: {<N.*>+}").unwrap();
+        let tree = parser.parse(&[("dogs", "NNS"), ("bark", "VBP"), ("John", "NNP"), ("Smith", "NNP")]);
+        assert_eq!(tree.to_string(), "(S (NP dogs/NNS) bark/VBP (NP John/NNP Smith/NNP))");
+    }
+
+    #[test]
+    fn test_chink() {
+        let grammar = "
+            NP:
+                {<.*>+}         # chunk everything
+                }<VBD|IN>+{     # chink sequences of VBD and IN
+        ";
+        let parser = RegexpParser::new(grammar).unwrap();
+        let tree = parser.parse(&sentence());
+        assert_eq!(tree.to_string(), "(S (NP the/DT little/JJ yellow/JJ dog/NN) barked/VBD at/IN (NP the/DT cat/NN))");
+    }
+
+    #[test]
+    fn test_cascade() {
+        let grammar = "NP: {<DT>?<JJ>*<NN>}\nPP: {<IN><NP>}\nVP: {<VBD><PP>}";
+        let parser = RegexpParser::new(grammar).unwrap();
+        let tree = parser.parse(&sentence());
+        assert_eq!(tree.to_string(), "(S (NP the/DT little/JJ yellow/JJ dog/NN) (VP barked/VBD (PP at/IN (NP the/DT cat/NN))))");
+    }
+
+    #[test]
+    fn test_adjacent_chunks_stay_separate() {
+        let parser = RegexpParser::new("NP: {<NN>}").unwrap();
+        let tree = parser.parse(&[("a", "NN"), ("b", "NN")]);
+        assert_eq!(tree.to_string(), "(S (NP a/NN) (NP b/NN))");
+    }
+
+    #[test]
+    fn test_colon_and_hash_tags() {
+        let sentence = [("$", "$"), ("5", "CD"), (":", ":"), ("#", "#"), ("3", "CD")];
+        let parser = RegexpParser::new("MONEY:\n{<[$#]><CD>} # amounts\nSEP: {<:>}").unwrap();
+        assert_eq!(parser.parse(&sentence).to_string(), "(S (MONEY $/$ 5/CD) (SEP :/:) (MONEY #/# 3/CD))");
+
+        let parser = RegexpParser::new("X\\:Y: {<\\#><CD>}\n}<\\:>{").unwrap();
+        let tree = parser.parse(&sentence);
+        assert_eq!(tree.to_string(), "(S $/$ 5/CD :/: (X:Y #/# 3/CD))");
+        assert!(RegexpParser::new("NP: {<DT>} \\# not a comment").is_err());
+    }
+
+    #[test]
+    fn test_invalid_grammar() {
+        assert!(RegexpParser::new("{<DT>}").is_err());
+        assert!(RegexpParser::new("NP: <DT>").is_err());
+        assert!(RegexpParser::new("NP: {<DT}").is_err());
+    }
+}
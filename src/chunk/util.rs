@@ -0,0 +1,246 @@
+use std::collections::BTreeSet;
+
+use anyhow::anyhow;
+
+use crate::chunk::ChunkTree;
+use crate::metrics::scores::{ratio, weighted_harmonic_mean};
+use crate::tree::Child;
+
+/// Converts a chunk tree to (token, tag, IOB tag) triples, as in CoNLL-2000 data.
+/// The first token in a chunk gets `B-` plus the chunk label, the other tokens in the chunk `I-` plus the label.
+/// Tokens outside of chunks get `O`. Fails for trees with chunks inside chunks.
+///
+/// ```
+/// use rltk::chunk::ChunkTree;
+/// use rltk::chunk::util::tree_to_iob;
+/// use rltk::tree::Child;
+///
+/// let tree = ChunkTree::new("S", vec![
+///     Child::Tree(ChunkTree::new("NP", vec![Child::Leaf(("the", "DT")), Child::Leaf(("dog", "NN"))])),
+///     Child::Leaf(("barked", "VBD")),
+/// ]);
+/// assert_eq!(tree_to_iob(&tree).unwrap(), vec![("the", "DT", "B-NP".to_string()), ("dog", "NN", "I-NP".to_string()),
+///     ("barked", "VBD", "O".to_string())]);
+/// ```
+pub fn tree_to_iob<'a>(tree: &ChunkTree<'a>) -> anyhow::Result<Vec<(&'a str, &'a str, String)>> {
+    let mut triples = Vec::new();
+    for child in &tree.children {
+        match child {
+            Child::Leaf((token, tag)) => triples.push((*token, *tag, "O".to_string())),
+            Child::Tree(chunk) => {
+                for (index, node) in chunk.children.iter().enumerate() {
+                    let (token, tag) = match node {
+                        Child::Leaf(leaf) => *leaf,
+                        Child::Tree(_) => return Err(anyhow!("tree is too deeply nested for IOB tags: {}", tree)),
+                    };
+                    let prefix = if index == 0 { "B" } else { "I" };
+                    triples.push((token, tag, format!("{}-{}", prefix, chunk.label)));
+                }
+            }
+        }
+    }
+    Ok(triples)
+}
+
+/// Converts (token, tag, IOB tag) triples to a chunk tree with the given root label.
+/// An `I-` tag that does not continue a chunk with the same label starts a new chunk, like a `B-` tag.
+pub fn iob_to_tree<'a, S: AsRef<str>>(triples: &[(&'a str, &'a str, S)], root_label: &str) -> anyhow::Result<ChunkTree<'a>> {
+    let mut children: Vec<Child<(&'a str, &'a str)>> = Vec::new();
+    let mut in_chunk = false;
+    for (token, tag, iob) in triples {
+        let iob = iob.as_ref();
+        let leaf = Child::Leaf((*token, *tag));
+        if iob == "O" {
+            children.push(leaf);
+            in_chunk = false;
+            continue;
+        }
+        let (prefix, label) = iob.split_once('-').ok_or_else(|| anyhow!("invalid IOB tag: {}", iob))?;
+        match (prefix, children.last_mut()) {
+            ("I", Some(Child::Tree(chunk))) if in_chunk && chunk.label == label => chunk.children.push(leaf),
+            ("B" | "I", _) => {
+                children.push(Child::Tree(ChunkTree::new(label, vec![leaf])));
+                in_chunk = true;
+            }
+            _ => return Err(anyhow!("invalid IOB tag: {}", iob)),
+        }
+    }
+    Ok(ChunkTree::new(root_label, children))
+}
+
+/// Reads one sentence in CoNLL-2000 format: a line per token with the token, its tag and its IOB tag, separated by whitespace.
+/// Blank lines are skipped. The root label is `S`.
+pub fn conll_str_to_tree(text: &str) -> anyhow::Result<ChunkTree<'_>> {
+    let mut triples = Vec::new();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields[..] {
+            [token, tag, iob] => triples.push((token, tag, iob)),
+            _ => return Err(anyhow!("expected token, tag and IOB tag: {}", line)),
+        }
+    }
+    iob_to_tree(&triples, "S")
+}
+
+/// Scores predicted chunks against gold chunks, accumulated over sentences.
+///
+/// A predicted chunk is correct if a gold chunk has the same label and spans exactly the same tokens.
+/// Only the outermost chunks are compared.
+#[derive(Clone, Debug, Default)]
+pub struct ChunkScore {
+    correct: usize,
+    guessed: usize,
+    gold: usize,
+    correct_tags: usize,
+    tags: usize,
+}
+
+impl ChunkScore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the chunks of a sentence: the gold tree and the predicted tree for the same tokens
+    pub fn score(&mut self, gold: &ChunkTree, guessed: &ChunkTree) {
+        let gold_chunks = chunk_spans(gold);
+        let guessed_chunks = chunk_spans(guessed);
+        self.gold += gold_chunks.len();
+        self.guessed += guessed_chunks.len();
+        self.correct += gold_chunks.intersection(&guessed_chunks).count();
+
+        let gold_tags = iob_tags(gold);
+        let guessed_tags = iob_tags(guessed);
+        self.tags += gold_tags.len();
+        self.correct_tags += gold_tags.iter().zip(&guessed_tags).filter(|(g, p)| g == p).count();
+    }
+
+    /// Fraction of the predicted chunks that are correct. None if no chunks were predicted
+    pub fn precision(&self) -> Option<f64> {
+        ratio(self.correct, self.guessed)
+    }
+
+    /// Fraction of the gold chunks that were predicted. None if there are no gold chunks
+    pub fn recall(&self) -> Option<f64> {
+        ratio(self.correct, self.gold)
+    }
+
+    /// Harmonic mean of precision and recall. None if either is undefined
+    pub fn f_measure(&self) -> Option<f64> {
+        Some(weighted_harmonic_mean(self.precision()?, self.recall()?, 0.5))
+    }
+
+    /// Fraction of the tokens with the gold IOB tag. None if no tokens were scored
+    pub fn accuracy(&self) -> Option<f64> {
+        ratio(self.correct_tags, self.tags)
+    }
+
+    /// number of gold chunks that were not predicted
+    pub fn missed(&self) -> usize {
+        self.gold - self.correct
+    }
+
+    /// number of predicted chunks that are not in gold
+    pub fn incorrect(&self) -> usize {
+        self.guessed - self.correct
+    }
+}
+
+/// (start, end, label) of the outermost chunks, in token positions
+fn chunk_spans(tree: &ChunkTree) -> BTreeSet<(usize, usize, String)> {
+    let mut spans = BTreeSet::new();
+    let mut position = 0;
+    for child in &tree.children {
+        match child {
+            Child::Leaf(_) => position += 1,
+            Child::Tree(chunk) => {
+                let length = chunk.leaves().len();
+                spans.insert((position, position + length, chunk.label.clone()));
+                position += length;
+            }
+        }
+    }
+    spans
+}
+
+/// IOB tags per token for the outermost chunks
+fn iob_tags(tree: &ChunkTree) -> Vec<String> {
+    let mut tags = Vec::new();
+    for child in &tree.children {
+        match child {
+            Child::Leaf(_) => tags.push("O".to_string()),
+            Child::Tree(chunk) => {
+                let length = chunk.leaves().len();
+                tags.push(format!("B-{}", chunk.label));
+                tags.extend((1..length).map(|_| format!("I-{}", chunk.label)));
+            }
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::ChunkParser;
+    use crate::chunk::regexp::RegexpParser;
+
+    const CONLL: &str = "
+        He PRP B-NP
+        reckons VBZ B-VP
+        the DT B-NP
+        current JJ I-NP
+        account NN I-NP
+        deficit NN I-NP
+        will MD B-VP
+        narrow VB I-VP
+        . . O
+    ";
+
+    #[test]
+    fn test_conll_round_trip() {
+        let tree = conll_str_to_tree(CONLL).unwrap();
+        assert_eq!(tree.to_string(), "(S (NP He/PRP) (VP reckons/VBZ) (NP the/DT current/JJ account/NN deficit/NN) \
+            (VP will/MD narrow/VB) ./.)");
+        let triples = tree_to_iob(&tree).unwrap();
+        assert_eq!(triples[3], ("current", "JJ", "I-NP".to_string()));
+        assert_eq!(iob_to_tree(&triples, "S").unwrap(), tree);
+    }
+
+    #[test]
+    fn test_iob_to_tree_lenient() {
+        let tree = iob_to_tree(&[("a", "DT", "I-NP"), ("b", "NN", "I-VP"), ("c", "NN", "O"), ("d", "NN", "I-NP")], "S").unwrap();
+        assert_eq!(tree.to_string(), "(S (NP a/DT) (VP b/NN) c/NN (NP d/NN))");
+        assert!(iob_to_tree(&[("a", "DT", "X-NP")], "S").is_err());
+        assert!(iob_to_tree(&[("a", "DT", "NP")], "S").is_err());
+    }
+
+    #[test]
+    fn test_nested_tree_is_not_iob() {
+        let parser = RegexpParser::new("NP: {<DT><NN>}\nPP: {<IN><NP>}").unwrap();
+        let tree = parser.parse(&[("in", "IN"), ("the", "DT"), ("house", "NN")]);
+        assert!(tree_to_iob(&tree).is_err());
+    }
+
+    #[test]
+    fn test_chunk_score() {
+        let gold = conll_str_to_tree(CONLL).unwrap();
+        let parser = RegexpParser::new("NP: {<DT>?<JJ>*<NN>+}").unwrap();
+        let score = parser.evaluate(&[gold]);
+
+        // only the second of the NPs is found, no VPs
+        assert_eq!(score.precision(), Some(1.0));
+        assert_eq!(score.recall(), Some(0.25));
+        assert_eq!(score.f_measure(), Some(0.4));
+        assert_eq!(score.missed(), 3);
+        assert_eq!(score.incorrect(), 0);
+        // He, reckons, will and narrow are wrong
+        assert_eq!(score.accuracy(), Some(5.0 / 9.0));
+    }
+
+    #[test]
+    fn test_empty_score() {
+        let score = ChunkScore::new();
+        assert_eq!(score.precision(), None);
+        assert_eq!(score.f_measure(), None);
+    }
+}
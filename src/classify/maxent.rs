@@ -0,0 +1,329 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+use anyhow::anyhow;
+
+use crate::classify::{Classifier, FeatureSet};
+use crate::mat::csr::CsrMat;
+
+/// Settings for training a [MaxentClassifier]
+#[derive(Clone, Debug)]
+pub struct MaxentParameters {
+    /// strength of the L2 regularization of the feature weights (not of the per label biases), finite and not negative
+    pub l2: f64,
+    /// maximum number of L-BFGS iterations
+    pub max_iterations: usize,
+    /// stop when the relative improvement of the objective, or the size of its gradient, gets below this.
+    /// Finite and not negative
+    pub tolerance: f64,
+    /// weight of the training items per label, 1 for labels that are not in the map.
+    /// Weights should be finite and not negative, and only for labels in the training data
+    pub class_weights: HashMap<String, f64>,
+}
+
+impl Default for MaxentParameters {
+    fn default() -> Self {
+        Self { l2: 1.0, max_iterations: 100, tolerance: 1e-6, class_weights: HashMap::new() }
+    }
+}
+
+/// Maximum entropy classifier, also known as multinomial logistic regression.
+///
+/// The probability of a label is proportional to the exponent of the bias of the label plus the weighted sum of the feature values.
+/// The weights are trained with L-BFGS to minimize the (class weighted) negative log likelihood of the training labels,
+/// plus an L2 penalty on the weights.
+///
+/// ```
+/// use rltk::classify::{bag_of_words, Classifier};
+/// use rltk::classify::maxent::{MaxentClassifier, MaxentParameters};
+///
+/// let corpus = vec![
+///     (bag_of_words(["great", "fun", "film"].iter()), "pos"),
+///     (bag_of_words(["fun", "and", "moving"].iter()), "pos"),
+///     (bag_of_words(["boring", "film"].iter()), "neg"),
+///     (bag_of_words(["dull", "and", "boring"].iter()), "neg"),
+/// ];
+/// let classifier = MaxentClassifier::train(&corpus, &MaxentParameters::default()).unwrap();
+/// assert_eq!(classifier.classify(&bag_of_words(["fun", "film"].iter())), "pos");
+/// assert!(classifier.prob_classify(&bag_of_words(["boring"].iter()))["neg"] > 0.5);
+/// ```
+pub struct MaxentClassifier {
+    labels: Vec<String>,
+    /// column index per feature name, empty when trained on a matrix
+    features: HashMap<String, usize>,
+    columns: usize,
+    /// per label, the weights of the columns followed by the bias
+    weights: Vec<Vec<f64>>,
+}
+
+impl MaxentClassifier {
+    /// Trains on (features, label) pairs
+    pub fn train(corpus: &[(FeatureSet, &str)], parameters: &MaxentParameters) -> anyhow::Result<Self> {
+        let names: BTreeSet<&str> = corpus.iter().flat_map(|(features, _)| features.keys().copied()).collect();
+        let features: HashMap<String, usize> = names.into_iter().enumerate().map(|(index, name)| (name.to_string(), index)).collect();
+        let rows = corpus.iter()
+            .map(|(item, _)| {
+                let mut row: Vec<(usize, f64)> = item.iter().map(|(name, value)| (features[*name], *value)).collect();
+                row.sort_by_key(|(column, _)| *column);
+                row
+            })
+            .collect();
+        let labels: Vec<&str> = corpus.iter().map(|(_, label)| *label).collect();
+        let mut classifier = Self::train_matrix(&CsrMat::from_sparse_rows(rows), &labels, parameters)?;
+        classifier.features = features;
+        Ok(classifier)
+    }
+
+    /// Trains on sparse feature vectors, the rows of the matrix, with a label per row.
+    /// The classifier can then classify rows with [MaxentClassifier::prob_classify_row], but not feature sets.
+    pub fn train_matrix(x: &CsrMat<f64>, labels: &[&str], parameters: &MaxentParameters) -> anyhow::Result<Self> {
+        if x.rows() != labels.len() {
+            return Err(anyhow!("{} rows, but {} labels", x.rows(), labels.len()));
+        }
+        if labels.is_empty() {
+            return Err(anyhow!("empty training corpus"));
+        }
+        if !parameters.l2.is_finite() || parameters.l2 < 0.0 {
+            return Err(anyhow!("invalid regularization {}", parameters.l2));
+        }
+        if !parameters.tolerance.is_finite() || parameters.tolerance < 0.0 {
+            return Err(anyhow!("invalid tolerance {}", parameters.tolerance));
+        }
+        let label_set: Vec<String> = labels.iter().copied().collect::<BTreeSet<_>>().into_iter().map(str::to_string).collect();
+        let label_index: HashMap<&str, usize> = label_set.iter().enumerate().map(|(index, label)| (label.as_str(), index)).collect();
+        for (label, weight) in &parameters.class_weights {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(anyhow!("invalid class weight {} for label {}", weight, label));
+            }
+            if !label_index.contains_key(label.as_str()) {
+                return Err(anyhow!("class weight for label {}, which is not in the training data", label));
+            }
+        }
+        let targets: Vec<usize> = labels.iter().map(|label| label_index[label]).collect();
+        let sample_weights: Vec<f64> = labels.iter()
+            .map(|label| parameters.class_weights.get(*label).copied().unwrap_or(1.0))
+            .collect();
+        let columns = (0..x.rows()).flat_map(|row| x.row(row)).map(|(column, _)| column + 1).max().unwrap_or(0);
+
+        let mut classifier = Self { labels: label_set, features: HashMap::new(), columns, weights: Vec::new() };
+        let objective = |parameters_vector: &[f64]| classifier.objective(parameters_vector, x, &targets, &sample_weights, parameters.l2);
+        let optimum = lbfgs(objective, vec![0.0; classifier.labels.len() * (columns + 1)], parameters.max_iterations, parameters.tolerance);
+        classifier.weights = optimum.chunks(columns + 1).map(<[f64]>::to_vec).collect();
+        Ok(classifier)
+    }
+
+    /// the probability of every label for a row of a matrix, in the order of the labels
+    pub fn prob_classify_row(&self, x: &CsrMat<f64>, row: usize) -> Vec<f64> {
+        self.probabilities(&self.weights_as_slices(), x.row(row))
+    }
+
+    fn weights_as_slices(&self) -> Vec<&[f64]> {
+        self.weights.iter().map(Vec::as_slice).collect()
+    }
+
+    /// softmax of the scores of the labels for a sparse feature vector, ignoring columns that were not seen in training
+    fn probabilities(&self, weights: &[&[f64]], row: impl Iterator<Item=(usize, f64)> + Clone) -> Vec<f64> {
+        let scores: Vec<f64> = weights.iter()
+            .map(|label_weights| {
+                let bias = label_weights[self.columns];
+                bias + row.clone().filter(|(column, _)| *column < self.columns).map(|(column, value)| label_weights[column] * value).sum::<f64>()
+            })
+            .collect();
+        let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let exps: Vec<f64> = scores.iter().map(|score| (score - max).exp()).collect();
+        let total: f64 = exps.iter().sum();
+        exps.into_iter().map(|exp| exp / total).collect()
+    }
+
+    /// the penalized negative log likelihood of the training data, and its gradient
+    fn objective(&self, parameters: &[f64], x: &CsrMat<f64>, targets: &[usize], sample_weights: &[f64], l2: f64) -> (f64, Vec<f64>) {
+        let width = self.columns + 1;
+        let weights: Vec<&[f64]> = parameters.chunks(width).collect();
+        let mut value = 0.0;
+        let mut gradient = vec![0.0; parameters.len()];
+        for (row, (&target, &sample_weight)) in targets.iter().zip(sample_weights).enumerate() {
+            let probabilities = self.probabilities(&weights, x.row(row));
+            value -= sample_weight * probabilities[target].max(f64::MIN_POSITIVE).ln();
+            for (label, probability) in probabilities.into_iter().enumerate() {
+                let error = sample_weight * (probability - if label == target { 1.0 } else { 0.0 });
+                let label_gradient = &mut gradient[label * width..(label + 1) * width];
+                for (column, feature_value) in x.row(row) {
+                    label_gradient[column] += error * feature_value;
+                }
+                label_gradient[self.columns] += error;
+            }
+        }
+        for (index, parameter) in parameters.iter().enumerate() {
+            if index % width != self.columns {
+                value += 0.5 * l2 * parameter * parameter;
+                gradient[index] += l2 * parameter;
+            }
+        }
+        (value, gradient)
+    }
+}
+
+impl Classifier for MaxentClassifier {
+    fn labels(&self) -> Vec<&str> {
+        self.labels.iter().map(String::as_str).collect()
+    }
+
+    /// Features that were not seen in training are ignored
+    fn prob_classify(&self, features: &FeatureSet) -> BTreeMap<String, f64> {
+        let row: Vec<(usize, f64)> = features.iter()
+            .filter_map(|(name, value)| self.features.get(*name).map(|column| (*column, *value)))
+            .collect();
+        let probabilities = self.probabilities(&self.weights_as_slices(), row.into_iter());
+        self.labels.iter().cloned().zip(probabilities).collect()
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Minimizes a function, given as a closure that returns its value and gradient, with limited memory BFGS and a backtracking line search
+fn lbfgs(f: impl Fn(&[f64]) -> (f64, Vec<f64>), mut x: Vec<f64>, max_iterations: usize, tolerance: f64) -> Vec<f64> {
+    const MEMORY: usize = 10;
+    let mut history: VecDeque<(Vec<f64>, Vec<f64>, f64)> = VecDeque::new();
+    let (mut value, mut gradient) = f(&x);
+    for _ in 0..max_iterations {
+        if dot(&gradient, &gradient).sqrt() < tolerance {
+            break;
+        }
+        // two loop recursion for the search direction
+        let mut direction: Vec<f64> = gradient.iter().map(|g| -g).collect();
+        let mut alphas = Vec::with_capacity(history.len());
+        for (s, y, rho) in history.iter().rev() {
+            let alpha = rho * dot(s, &direction);
+            direction.iter_mut().zip(y).for_each(|(d, y)| *d -= alpha * y);
+            alphas.push(alpha);
+        }
+        if let Some((s, y, _)) = history.back() {
+            let scale = dot(s, y) / dot(y, y);
+            direction.iter_mut().for_each(|d| *d *= scale);
+        } else {
+            let scale = 1.0 / dot(&gradient, &gradient).sqrt().max(1.0);
+            direction.iter_mut().for_each(|d| *d *= scale);
+        }
+        for ((s, y, rho), alpha) in history.iter().zip(alphas.into_iter().rev()) {
+            let beta = rho * dot(y, &direction);
+            direction.iter_mut().zip(s).for_each(|(d, s)| *d += (alpha - beta) * s);
+        }
+
+        let slope = dot(&gradient, &direction);
+        if slope >= 0.0 {
+            // not a descent direction, start over from steepest descent
+            history.clear();
+            continue;
+        }
+        let mut step = 1.0;
+        let mut accepted = None;
+        for _ in 0..50 {
+            let candidate: Vec<f64> = x.iter().zip(&direction).map(|(x, d)| x + step * d).collect();
+            let (candidate_value, candidate_gradient) = f(&candidate);
+            if candidate_value <= value + 1e-4 * step * slope {
+                accepted = Some((candidate, candidate_value, candidate_gradient));
+                break;
+            }
+            step *= 0.5;
+        }
+        let Some((next, next_value, next_gradient)) = accepted else { break };
+
+        let s: Vec<f64> = next.iter().zip(&x).map(|(a, b)| a - b).collect();
+        let y: Vec<f64> = next_gradient.iter().zip(&gradient).map(|(a, b)| a - b).collect();
+        let sy = dot(&s, &y);
+        if sy > 1e-10 {
+            if history.len() == MEMORY {
+                history.pop_front();
+            }
+            history.push_back((s, y, 1.0 / sy));
+        }
+        let improvement = value - next_value;
+        x = next;
+        value = next_value;
+        gradient = next_gradient;
+        if improvement <= tolerance * value.abs().max(1.0) {
+            break;
+        }
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unregularized() -> MaxentParameters {
+        MaxentParameters { l2: 0.0, max_iterations: 500, tolerance: 1e-12, ..MaxentParameters::default() }
+    }
+
+    #[test]
+    fn test_maximum_likelihood() {
+        // without regularization the probabilities are the relative frequencies of the labels, per value of the feature
+        let x = CsrMat::from_sparse_rows(vec![vec![(0, 1.0)], vec![(0, 1.0)], vec![(0, 1.0)], vec![(0, 1.0)], vec![], vec![]]);
+        let labels = ["a", "a", "a", "b", "a", "b"];
+        let classifier = MaxentClassifier::train_matrix(&x, &labels, &unregularized()).unwrap();
+        assert!((classifier.prob_classify_row(&x, 0)[0] - 0.75).abs() < 1e-4);
+        assert!((classifier.prob_classify_row(&x, 4)[0] - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_regularization_and_class_weights() {
+        let corpus = vec![
+            (FeatureSet::from([("x", 1.0)]), "a"),
+            (FeatureSet::from([("x", 1.0)]), "a"),
+            (FeatureSet::from([("x", 1.0)]), "b"),
+            (FeatureSet::from([("y", 1.0)]), "b"),
+        ];
+        let item = FeatureSet::from([("x", 1.0)]);
+        let free = MaxentClassifier::train(&corpus, &unregularized()).unwrap();
+        let regularized = MaxentClassifier::train(&corpus, &MaxentParameters { l2: 10.0, ..unregularized() }).unwrap();
+        assert!((free.prob_classify(&item)["a"] - 2.0 / 3.0).abs() < 1e-4);
+        assert!(regularized.prob_classify(&item)["a"] < free.prob_classify(&item)["a"]);
+
+        let class_weights = HashMap::from([("b".to_string(), 3.0)]);
+        let weighted = MaxentClassifier::train(&corpus, &MaxentParameters { class_weights, ..unregularized() }).unwrap();
+        // 2 a's against 3 times 1 b
+        assert!((weighted.prob_classify(&item)["a"] - 0.4).abs() < 1e-4);
+        assert_eq!(weighted.classify(&item), "b");
+    }
+
+    #[test]
+    fn test_multiclass() {
+        let corpus: Vec<(FeatureSet, &str)> = vec![
+            (FeatureSet::from([("red", 1.0)]), "apple"),
+            (FeatureSet::from([("red", 1.0), ("round", 1.0)]), "apple"),
+            (FeatureSet::from([("yellow", 1.0), ("long", 1.0)]), "banana"),
+            (FeatureSet::from([("yellow", 1.0)]), "banana"),
+            (FeatureSet::from([("orange", 1.0), ("round", 1.0)]), "orange"),
+        ];
+        let classifier = MaxentClassifier::train(&corpus, &MaxentParameters::default()).unwrap();
+        assert_eq!(classifier.labels(), vec!["apple", "banana", "orange"]);
+        for (features, label) in &corpus {
+            assert_eq!(classifier.classify(features), *label);
+        }
+        let probabilities = classifier.prob_classify(&FeatureSet::from([("unknown", 1.0)]));
+        assert!((probabilities.values().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_training() {
+        let x = CsrMat::from_sparse_rows(vec![vec![(0, 1.0)]]);
+        assert!(MaxentClassifier::train_matrix(&x, &["a", "b"], &MaxentParameters::default()).is_err());
+        assert!(MaxentClassifier::train(&[], &MaxentParameters::default()).is_err());
+        assert!(MaxentClassifier::train_matrix(&x, &["a"], &MaxentParameters { l2: -1.0, ..MaxentParameters::default() }).is_err());
+        for l2 in [f64::NAN, f64::INFINITY] {
+            assert!(MaxentClassifier::train_matrix(&x, &["a"], &MaxentParameters { l2, ..MaxentParameters::default() }).is_err());
+        }
+        for tolerance in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(MaxentClassifier::train_matrix(&x, &["a"], &MaxentParameters { tolerance, ..MaxentParameters::default() }).is_err());
+        }
+        for (label, weight) in [("a", -1.0), ("a", f64::NAN), ("a", f64::INFINITY), ("b", 2.0)] {
+            let class_weights = HashMap::from([(label.to_string(), weight)]);
+            let parameters = MaxentParameters { class_weights, ..MaxentParameters::default() };
+            assert!(MaxentClassifier::train_matrix(&x, &["a"], &parameters).is_err(), "weight {} for {}", weight, label);
+        }
+        let parameters = MaxentParameters { class_weights: HashMap::from([("a".to_string(), 0.0)]), ..MaxentParameters::default() };
+        assert!(MaxentClassifier::train_matrix(&x, &["a"], &parameters).is_ok());
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::{BTreeMap, HashMap};
+
+pub mod maxent;
+pub mod naive_bayes;
+
+/// The features of an item to classify: feature names with their values, like word counts. Missing features have value 0
+pub type FeatureSet<'a> = HashMap<&'a str, f64>;
+
+/// A classifier assigns one of a fixed set of labels to a feature set
+pub trait Classifier {
+    /// all labels, sorted
+    fn labels(&self) -> Vec<&str>;
+
+    /// the probability of every label
+    fn prob_classify(&self, features: &FeatureSet) -> BTreeMap<String, f64>;
+
+    /// the most probable label, the first one in the order of labels in case of a tie
+    fn classify(&self, features: &FeatureSet) -> String {
+        let mut best: Option<(String, f64)> = None;
+        for (label, probability) in self.prob_classify(features) {
+            if best.as_ref().is_none_or(|(_, best_probability)| probability > *best_probability) {
+                best = Some((label, probability));
+            }
+        }
+        best.map(|(label, _)| label).unwrap_or_default()
+    }
+}
+
+/// the fraction of gold (features, label) pairs for which the classifier predicts the label
+pub fn accuracy<C: Classifier + ?Sized>(classifier: &C, gold: &[(FeatureSet, &str)]) -> f64 {
+    if gold.is_empty() {
+        return 0.0;
+    }
+    let correct = gold.iter().filter(|(features, label)| classifier.classify(features) == *label).count();
+    correct as f64 / gold.len() as f64
+}
+
+/// counts of the tokens, as features for classification
+pub fn bag_of_words<'a>(tokens: impl Iterator<Item=&'a &'a str>) -> FeatureSet<'a> {
+    let mut features = FeatureSet::new();
+    for token in tokens {
+        *features.entry(*token).or_default() += 1.0;
+    }
+    features
+}
@@ -0,0 +1,185 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::anyhow;
+
+use crate::classify::{Classifier, FeatureSet};
+
+/// Multinomial Naive Bayes classifier.
+///
+/// Feature values are counts (or other non-negative weights, like tf-idf). The probability of a label given a feature set is
+/// proportional to P(label) times P(feature | label) to the power of the feature value, for every feature.
+/// P(feature | label) is estimated with additive (Lidstone) smoothing. Features that were not seen in training are ignored.
+///
+/// ```
+/// use rltk::classify::{bag_of_words, Classifier};
+/// use rltk::classify::naive_bayes::NaiveBayesClassifier;
+///
+/// let corpus = vec![
+///     (bag_of_words(["great", "fun", "film"].iter()), "pos"),
+///     (bag_of_words(["fun", "and", "moving"].iter()), "pos"),
+///     (bag_of_words(["boring", "film"].iter()), "neg"),
+///     (bag_of_words(["dull", "and", "boring"].iter()), "neg"),
+/// ];
+/// let classifier = NaiveBayesClassifier::train(&corpus, 1.0).unwrap();
+/// assert_eq!(classifier.classify(&bag_of_words(["fun", "film"].iter())), "pos");
+/// assert_eq!(classifier.classify(&bag_of_words(["boring"].iter())), "neg");
+/// ```
+pub struct NaiveBayesClassifier {
+    labels: Vec<String>,
+    /// per label
+    log_priors: Vec<f64>,
+    /// per feature, per label
+    log_likelihoods: HashMap<String, Vec<f64>>,
+}
+
+impl NaiveBayesClassifier {
+    /// Trains on (features, label) pairs, with smoothing `alpha` (1 is Laplace smoothing).
+    /// Fails for an empty corpus, negative feature values and an alpha that is not positive and finite.
+    pub fn train(corpus: &[(FeatureSet, &str)], alpha: f64) -> anyhow::Result<Self> {
+        if corpus.is_empty() {
+            return Err(anyhow!("empty training corpus"));
+        }
+        if !alpha.is_finite() || alpha <= 0.0 {
+            return Err(anyhow!("smoothing must be positive, not {}", alpha));
+        }
+        let labels: Vec<String> = corpus.iter().map(|(_, label)| *label).collect::<BTreeSet<_>>()
+            .into_iter().map(str::to_string).collect();
+        let label_index: HashMap<&str, usize> = labels.iter().enumerate().map(|(index, label)| (label.as_str(), index)).collect();
+
+        let mut label_counts = vec![0usize; labels.len()];
+        let mut feature_counts: HashMap<&str, Vec<f64>> = HashMap::new();
+        let mut totals = vec![0.0; labels.len()];
+        for (features, label) in corpus {
+            let label = label_index[label];
+            label_counts[label] += 1;
+            for (&feature, &value) in features {
+                if value < 0.0 {
+                    return Err(anyhow!("negative value {} for feature {}", value, feature));
+                }
+                feature_counts.entry(feature).or_insert_with(|| vec![0.0; labels.len()])[label] += value;
+                totals[label] += value;
+            }
+        }
+
+        let log_priors = label_counts.iter().map(|&count| (count as f64 / corpus.len() as f64).ln()).collect();
+        let vocabulary_size = feature_counts.len() as f64;
+        let log_likelihoods = feature_counts.into_iter()
+            .map(|(feature, counts)| {
+                let log_likelihoods = counts.iter().zip(&totals)
+                    .map(|(count, total)| ((count + alpha) / (total + alpha * vocabulary_size)).ln())
+                    .collect();
+                (feature.to_string(), log_likelihoods)
+            })
+            .collect();
+        Ok(Self { labels, log_priors, log_likelihoods })
+    }
+
+    /// Features that discriminate most between labels, by the ratio of the highest and the lowest P(feature | label).
+    /// Returns at most n (feature, label with the highest probability, ratio) triples, the most informative first.
+    pub fn most_informative_features(&self, n: usize) -> Vec<(&str, &str, f64)> {
+        let mut features: Vec<(&str, &str, f64)> = self.log_likelihoods.iter()
+            .map(|(feature, log_likelihoods)| {
+                let (best, max) = log_likelihoods.iter().enumerate()
+                    .fold((0, f64::NEG_INFINITY), |(best, max), (label, &value)| if value > max { (label, value) } else { (best, max) });
+                let min = log_likelihoods.iter().copied().fold(f64::INFINITY, f64::min);
+                (feature.as_str(), self.labels[best].as_str(), (max - min).exp())
+            })
+            .collect();
+        features.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.0.cmp(b.0)));
+        features.truncate(n);
+        features
+    }
+
+    /// per label, the log of P(label) times P(feature | label) to the power of the feature value, for all features
+    fn log_scores(&self, features: &FeatureSet) -> Vec<f64> {
+        let mut scores = self.log_priors.clone();
+        for (feature, value) in features {
+            if let Some(log_likelihoods) = self.log_likelihoods.get(*feature) {
+                for (score, log_likelihood) in scores.iter_mut().zip(log_likelihoods) {
+                    *score += value * log_likelihood;
+                }
+            }
+        }
+        scores
+    }
+}
+
+impl Classifier for NaiveBayesClassifier {
+    fn labels(&self) -> Vec<&str> {
+        self.labels.iter().map(String::as_str).collect()
+    }
+
+    fn prob_classify(&self, features: &FeatureSet) -> BTreeMap<String, f64> {
+        let scores = self.log_scores(features);
+        // normalize in log space, to avoid underflow for long documents
+        let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let total: f64 = scores.iter().map(|score| (score - max).exp()).sum();
+        self.labels.iter().zip(scores)
+            .map(|(label, score)| (label.clone(), (score - max).exp() / total))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classify::{accuracy, bag_of_words};
+
+    fn corpus() -> Vec<(FeatureSet<'static>, &'static str)> {
+        vec![
+            (bag_of_words(["Chinese", "Beijing", "Chinese"].iter()), "c"),
+            (bag_of_words(["Chinese", "Chinese", "Shanghai"].iter()), "c"),
+            (bag_of_words(["Chinese", "Macao"].iter()), "c"),
+            (bag_of_words(["Tokyo", "Japan", "Chinese"].iter()), "j"),
+        ]
+    }
+
+    #[test]
+    fn test_prob_classify() {
+        // the example from Manning, Raghavan & Schütze, Introduction to Information Retrieval, section 13.2
+        let classifier = NaiveBayesClassifier::train(&corpus(), 1.0).unwrap();
+        let test = bag_of_words(["Chinese", "Chinese", "Chinese", "Tokyo", "Japan"].iter());
+        let c: f64 = 0.75 * (3.0f64 / 7.0).powi(3) / 14.0 / 14.0;
+        let j: f64 = 0.25 * (2.0f64 / 9.0).powi(3) * (2.0 / 9.0) * (2.0 / 9.0);
+        let probabilities = classifier.prob_classify(&test);
+        assert!((probabilities["c"] - c / (c + j)).abs() < 1e-12);
+        assert!((probabilities["j"] - j / (c + j)).abs() < 1e-12);
+        assert_eq!(classifier.classify(&test), "c");
+        assert_eq!(classifier.labels(), vec!["c", "j"]);
+    }
+
+    #[test]
+    fn test_unknown_features_are_ignored() {
+        let classifier = NaiveBayesClassifier::train(&corpus(), 1.0).unwrap();
+        let probabilities = classifier.prob_classify(&bag_of_words(["Paris"].iter()));
+        assert!((probabilities["c"] - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_most_informative_features() {
+        let classifier = NaiveBayesClassifier::train(&corpus(), 1.0).unwrap();
+        let features = classifier.most_informative_features(3);
+        // P(Japan | j) = 2/9, P(Japan | c) = 1/14
+        assert_eq!(features[0].0, "Japan");
+        assert_eq!(features[0].1, "j");
+        assert!((features[0].2 - 28.0 / 9.0).abs() < 1e-12);
+        assert_eq!(features[1].0, "Tokyo");
+        assert_eq!(features.len(), 3);
+    }
+
+    #[test]
+    fn test_accuracy() {
+        let classifier = NaiveBayesClassifier::train(&corpus(), 1.0).unwrap();
+        assert_eq!(accuracy(&classifier, &corpus()), 1.0);
+    }
+
+    #[test]
+    fn test_invalid_training() {
+        assert!(NaiveBayesClassifier::train(&[], 1.0).is_err());
+        assert!(NaiveBayesClassifier::train(&corpus(), 0.0).is_err());
+        assert!(NaiveBayesClassifier::train(&corpus(), f64::NAN).is_err());
+        assert!(NaiveBayesClassifier::train(&corpus(), f64::INFINITY).is_err());
+        let negative = vec![(FeatureSet::from([("a", -1.0)]), "x")];
+        assert!(NaiveBayesClassifier::train(&negative, 1.0).is_err());
+    }
+}
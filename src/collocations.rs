@@ -0,0 +1,284 @@
+use std::cmp::Ordering;
+
+use crate::lm::probability::FreqDist;
+use crate::metrics::association::Marginals;
+use crate::util::ngrams;
+
+/// Finds n-grams of words that occur together more often than expected, scored by an association measure
+/// from [crate::metrics::association]
+pub trait CollocationFinder<'a, const N: usize> {
+    /// how often every n-gram occurs
+    fn ngram_fd(&self) -> &FreqDist<[&'a str; N]>;
+
+    fn ngram_fd_mut(&mut self) -> &mut FreqDist<[&'a str; N]>;
+
+    /// The score of an n-gram, None if it does not occur
+    fn score_ngram(&self, score_fn: impl Fn(&Marginals) -> f64, ngram: &[&str; N]) -> Option<f64>;
+
+    /// Removes the n-grams that occur less than min_freq times
+    fn apply_freq_filter(&mut self, min_freq: usize) {
+        self.ngram_fd_mut().retain(|_, count| count >= min_freq);
+    }
+
+    /// Removes the n-grams that contain a word for which filter is true, for instance a stopword
+    fn apply_word_filter(&mut self, filter: impl Fn(&str) -> bool) {
+        self.ngram_fd_mut().retain(|ngram, _| !ngram.iter().any(|word| filter(word)));
+    }
+
+    /// Removes the n-grams for which filter is true
+    fn apply_ngram_filter(&mut self, filter: impl Fn(&[&str]) -> bool) {
+        self.ngram_fd_mut().retain(|ngram, _| !filter(ngram));
+    }
+
+    /// All n-grams with their scores, from high to low. N-grams with equal scores are sorted alphabetically
+    fn score_ngrams(&self, score_fn: impl Fn(&Marginals) -> f64) -> Vec<([&'a str; N], f64)> {
+        let mut scores: Vec<([&'a str; N], f64)> = self.ngram_fd().iter()
+            .filter_map(|(ngram, _)| Some((*ngram, self.score_ngram(&score_fn, ngram)?)))
+            .collect();
+        scores.sort_by(|(ngram1, score1), (ngram2, score2)| {
+            score2.partial_cmp(score1).unwrap_or(Ordering::Equal).then_with(|| ngram1.cmp(ngram2))
+        });
+        scores
+    }
+
+    /// The n n-grams with the highest scores
+    fn nbest(&self, score_fn: impl Fn(&Marginals) -> f64, n: usize) -> Vec<[&'a str; N]> {
+        self.score_ngrams(score_fn).into_iter().take(n).map(|(ngram, _)| ngram).collect()
+    }
+}
+
+/// Finds pairs of words that occur together, next to each other or within a window
+///
+/// ```
+/// use rltk::collocations::{BigramCollocationFinder, CollocationFinder};
+/// use rltk::metrics::association::pmi;
+///
+/// let words = ["in", "new", "york", "and", "new", "york", "city", "a", "new", "car"];
+/// let mut finder = BigramCollocationFinder::from_words(&words, 2);
+/// finder.apply_freq_filter(2);
+/// assert_eq!(finder.nbest(pmi, 1), vec![["new", "york"]]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BigramCollocationFinder<'a> {
+    word_fd: FreqDist<&'a str>,
+    ngram_fd: FreqDist<[&'a str; 2]>,
+    window_size: usize,
+}
+
+impl<'a> BigramCollocationFinder<'a> {
+    /// word_fd counts the words and ngram_fd the pairs, which may be counted within windows of window_size words
+    pub fn new(word_fd: FreqDist<&'a str>, ngram_fd: FreqDist<[&'a str; 2]>, window_size: usize) -> Self {
+        Self { word_fd, ngram_fd, window_size }
+    }
+
+    /// Counts every word with each of the words that follow it within a window of window_size words (2 for adjacent words).
+    /// The count of a pair is divided by window_size - 1 for scoring
+    /// # Panics
+    /// if window_size is less than 2
+    pub fn from_words(words: &'a [&'a str], window_size: usize) -> Self {
+        assert!(window_size >= 2, "the window size should be at least 2");
+        let mut word_fd = FreqDist::new();
+        let mut ngram_fd = FreqDist::new();
+        for window in windows(words, window_size) {
+            let first = window[0].unwrap();
+            word_fd.increment(first);
+            for second in window[1..].iter().flatten() {
+                ngram_fd.increment([first, *second]);
+            }
+        }
+        Self::new(word_fd, ngram_fd, window_size)
+    }
+
+    pub fn word_fd(&self) -> &FreqDist<&'a str> {
+        &self.word_fd
+    }
+}
+
+impl<'a> CollocationFinder<'a, 2> for BigramCollocationFinder<'a> {
+    fn ngram_fd(&self) -> &FreqDist<[&'a str; 2]> {
+        &self.ngram_fd
+    }
+
+    fn ngram_fd_mut(&mut self) -> &mut FreqDist<[&'a str; 2]> {
+        &mut self.ngram_fd
+    }
+
+    fn score_ngram(&self, score_fn: impl Fn(&Marginals) -> f64, ngram: &[&str; 2]) -> Option<f64> {
+        let count = self.ngram_fd.get(&[ngram[0], ngram[1]]);
+        if count == 0 {
+            return None;
+        }
+        let marginals = Marginals::from_patterns(&[
+            ("ii", count as f64 / (self.window_size - 1) as f64),
+            ("ix", self.word_fd.get(&ngram[0]) as f64),
+            ("xi", self.word_fd.get(&ngram[1]) as f64),
+            ("xx", self.word_fd.total() as f64),
+        ]);
+        Some(score_fn(&marginals))
+    }
+}
+
+/// Finds triples of words that occur together
+///
+/// ```
+/// use rltk::collocations::{CollocationFinder, TrigramCollocationFinder};
+/// use rltk::metrics::association::raw_freq;
+///
+/// let words = ["one", "two", "three", "one", "two", "three", "four"];
+/// let finder = TrigramCollocationFinder::from_words(&words, 3);
+/// assert_eq!(finder.nbest(raw_freq, 1), vec![["one", "two", "three"]]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TrigramCollocationFinder<'a> {
+    word_fd: FreqDist<&'a str>,
+    bigram_fd: FreqDist<[&'a str; 2]>,
+    /// pairs of the first and third word
+    wildcard_fd: FreqDist<[&'a str; 2]>,
+    ngram_fd: FreqDist<[&'a str; 3]>,
+}
+
+impl<'a> TrigramCollocationFinder<'a> {
+    /// Counts every word with each pair of words that follow it within a window of window_size words (3 for adjacent words),
+    /// like nltk
+    /// # Panics
+    /// if window_size is less than 3
+    pub fn from_words(words: &'a [&'a str], window_size: usize) -> Self {
+        assert!(window_size >= 3, "the window size should be at least 3");
+        let mut word_fd = FreqDist::new();
+        let mut bigram_fd = FreqDist::new();
+        let mut wildcard_fd = FreqDist::new();
+        let mut ngram_fd = FreqDist::new();
+        for window in windows(words, window_size) {
+            let first = window[0].unwrap();
+            for (i, second) in window.iter().enumerate().skip(1) {
+                for third in &window[i + 1..] {
+                    word_fd.increment(first);
+                    let Some(second) = second else { continue };
+                    bigram_fd.increment([first, *second]);
+                    let Some(third) = third else { continue };
+                    wildcard_fd.increment([first, *third]);
+                    ngram_fd.increment([first, *second, *third]);
+                }
+            }
+        }
+        Self { word_fd, bigram_fd, wildcard_fd, ngram_fd }
+    }
+
+    pub fn word_fd(&self) -> &FreqDist<&'a str> {
+        &self.word_fd
+    }
+
+    /// A finder for the pairs of adjacent words
+    pub fn bigram_finder(&self) -> BigramCollocationFinder<'a> {
+        BigramCollocationFinder::new(self.word_fd.clone(), self.bigram_fd.clone(), 2)
+    }
+}
+
+impl<'a> CollocationFinder<'a, 3> for TrigramCollocationFinder<'a> {
+    fn ngram_fd(&self) -> &FreqDist<[&'a str; 3]> {
+        &self.ngram_fd
+    }
+
+    fn ngram_fd_mut(&mut self) -> &mut FreqDist<[&'a str; 3]> {
+        &mut self.ngram_fd
+    }
+
+    fn score_ngram(&self, score_fn: impl Fn(&Marginals) -> f64, ngram: &[&str; 3]) -> Option<f64> {
+        let [w1, w2, w3] = *ngram;
+        let count = self.ngram_fd.get(&[w1, w2, w3]);
+        if count == 0 {
+            return None;
+        }
+        let marginals = Marginals::trigram(
+            count,
+            [self.bigram_fd.get(&[w1, w2]), self.wildcard_fd.get(&[w1, w3]), self.bigram_fd.get(&[w2, w3])],
+            [self.word_fd.get(&w1), self.word_fd.get(&w2), self.word_fd.get(&w3)],
+            self.word_fd.total(),
+        );
+        Some(score_fn(&marginals))
+    }
+}
+
+/// The windows of window_size words that start at every word. The last ones are padded with None
+fn windows<'a>(words: &'a [&'a str], window_size: usize) -> Vec<Vec<Option<&'a str>>> {
+    let mut windows: Vec<Vec<Option<&str>>> = ngrams(words.iter(), window_size)
+        .map(|window| window.map(|word| Some(*word)).collect())
+        .collect();
+    for start in windows.len()..words.len() {
+        let mut window: Vec<Option<&str>> = words[start..].iter().map(|word| Some(*word)).collect();
+        window.resize(window_size, None);
+        windows.push(window);
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::association::{pmi, raw_freq};
+
+    #[test]
+    fn test_windows() {
+        let words = ["a", "b", "c"];
+        assert_eq!(windows(&words, 2), vec![vec![Some("a"), Some("b")], vec![Some("b"), Some("c")], vec![Some("c"), None]]);
+        assert_eq!(windows(&words[..1], 3), vec![vec![Some("a"), None, None]]);
+    }
+
+    #[test]
+    fn test_bigram_scores() {
+        let words = ["a", "b", "a", "b", "c"];
+        let finder = BigramCollocationFinder::from_words(&words, 2);
+        assert_eq!(finder.word_fd().total(), 5);
+        assert_eq!(finder.ngram_fd().get(&["a", "b"]), 2);
+        // ab and bc both occur 2.5 times as often as expected
+        let scores = finder.score_ngrams(pmi);
+        let ngrams: Vec<_> = scores.iter().map(|(ngram, _)| *ngram).collect();
+        assert_eq!(ngrams, vec![["a", "b"], ["b", "c"], ["b", "a"]]);
+        assert!((scores[0].1 - 2.5f64.log2()).abs() < 1e-12);
+        assert_eq!(finder.score_ngram(raw_freq, &["a", "b"]), Some(0.4));
+        assert_eq!(finder.score_ngram(raw_freq, &["c", "a"]), None);
+    }
+
+    #[test]
+    fn test_window_size() {
+        let words = ["a", "b", "a", "b", "c"];
+        let finder = BigramCollocationFinder::from_words(&words, 3);
+        assert_eq!(finder.word_fd().total(), 5);
+        assert_eq!(finder.ngram_fd().get(&["a", "b"]), 2);
+        assert_eq!(finder.ngram_fd().get(&["a", "a"]), 1);
+        assert_eq!(finder.ngram_fd().get(&["a", "c"]), 1);
+        // the count is divided by the number of words that follow in the window
+        assert_eq!(finder.score_ngram(raw_freq, &["a", "b"]), Some(0.2));
+    }
+
+    #[test]
+    fn test_filters() {
+        let words = ["a", "b", "a", "b", "c"];
+        let mut finder = BigramCollocationFinder::from_words(&words, 2);
+        finder.apply_word_filter(|word| word == "c");
+        assert_eq!(finder.nbest(pmi, 5), vec![["a", "b"], ["b", "a"]]);
+        finder.apply_ngram_filter(|ngram| ngram == ["b", "a"]);
+        assert_eq!(finder.nbest(pmi, 5), vec![["a", "b"]]);
+
+        let mut finder = BigramCollocationFinder::from_words(&words, 2);
+        finder.apply_freq_filter(2);
+        assert_eq!(finder.nbest(pmi, 5), vec![["a", "b"]]);
+        assert_eq!(finder.ngram_fd().total(), 2);
+    }
+
+    #[test]
+    fn test_trigrams() {
+        let words = ["a", "b", "c", "a", "b", "c"];
+        let finder = TrigramCollocationFinder::from_words(&words, 3);
+        assert_eq!(finder.word_fd().total(), 6);
+        assert_eq!(finder.ngram_fd().get(&["a", "b", "c"]), 2);
+        assert_eq!(finder.ngram_fd().total(), 4);
+        assert_eq!(finder.score_ngram(raw_freq, &["a", "b", "c"]), Some(2.0 / 6.0));
+        assert!((finder.score_ngram(pmi, &["a", "b", "c"]).unwrap() - 9f64.log2()).abs() < 1e-12);
+
+        let bigrams = finder.bigram_finder();
+        assert_eq!(bigrams.ngram_fd().get(&["a", "b"]), 2);
+        assert_eq!(bigrams.ngram_fd().get(&["c", "a"]), 1);
+        assert_eq!(bigrams.ngram_fd().total(), 5);
+    }
+}
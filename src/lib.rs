@@ -1,8 +1,15 @@
+pub mod chunk;
+pub mod classify;
+pub mod collocations;
 pub mod lm;
 pub mod util;
 pub mod metrics;
 pub mod mat;
+pub mod parse;
 pub mod pipelines;
+pub mod stem;
+pub mod tag;
+pub mod tree;
 mod worker;
 
 #[cfg(test)]
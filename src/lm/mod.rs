@@ -1,2 +1,3 @@
 pub mod preprocessing;
-pub mod mle;
\ No newline at end of file
+pub mod mle;
+pub mod probability;
\ No newline at end of file
@@ -0,0 +1,177 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Frequency distribution: counts how often every sample occurs
+#[derive(Clone, Debug)]
+pub struct FreqDist<T: Hash + Eq> {
+    counts: HashMap<T, usize>,
+    total: usize,
+}
+
+impl<T: Hash + Eq> FreqDist<T> {
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+            total: 0,
+        }
+    }
+
+    pub fn increment(&mut self, sample: T) {
+        self.add(sample, 1);
+    }
+
+    pub fn add(&mut self, sample: T, count: usize) {
+        *self.counts.entry(sample).or_insert(0) += count;
+        self.total += count;
+    }
+
+    pub fn get(&self, sample: &T) -> usize {
+        self.counts.get(sample).copied().unwrap_or(0)
+    }
+
+    /// total number of samples counted (N)
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// number of distinct samples (B)
+    pub fn bins(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(&T, usize)> {
+        self.counts.iter().map(|(sample, count)| (sample, *count))
+    }
+
+    /// Keeps only the samples for which keep(sample, count) is true, and updates the total
+    pub fn retain(&mut self, mut keep: impl FnMut(&T, usize) -> bool) {
+        self.counts.retain(|sample, count| keep(sample, *count));
+        self.total = self.counts.values().sum();
+    }
+
+    /// Maximum likelihood estimate: count / N
+    pub fn mle(&self, sample: &T) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.get(sample) as f64 / self.total as f64
+        }
+    }
+
+    /// Lidstone estimate: (count + gamma) / (N + bins * gamma)
+    ///
+    /// bins is the number of possible samples, including those that were never seen, so it should be at least `self.bins()`
+    pub fn lidstone(&self, sample: &T, gamma: f64, bins: usize) -> f64 {
+        let denominator = self.total as f64 + bins as f64 * gamma;
+        if denominator == 0.0 {
+            0.0
+        } else {
+            (self.get(sample) as f64 + gamma) / denominator
+        }
+    }
+
+    /// Laplace (add one) estimate
+    pub fn laplace(&self, sample: &T, bins: usize) -> f64 {
+        self.lidstone(sample, 1.0, bins)
+    }
+}
+
+impl<T: Hash + Eq + Ord> FreqDist<T> {
+    /// the most frequent sample, the smallest one on a tie
+    pub fn max(&self) -> Option<&T> {
+        self.counts.iter().max_by_key(|(sample, count)| (**count, Reverse(*sample))).map(|(sample, _)| sample)
+    }
+}
+
+impl<T: Hash + Eq> Default for FreqDist<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for FreqDist<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(samples: I) -> Self {
+        let mut dist = Self::new();
+        samples.into_iter().for_each(|sample| dist.increment(sample));
+        dist
+    }
+}
+
+/// A frequency distribution per condition, for instance the tags that follow a tag
+#[derive(Clone, Debug)]
+pub struct ConditionalFreqDist<C: Hash + Eq, T: Hash + Eq> {
+    dists: HashMap<C, FreqDist<T>>,
+}
+
+impl<C: Hash + Eq, T: Hash + Eq> ConditionalFreqDist<C, T> {
+    pub fn new() -> Self {
+        Self {
+            dists: HashMap::new()
+        }
+    }
+
+    pub fn increment(&mut self, condition: C, sample: T) {
+        self.dists.entry(condition).or_default().increment(sample);
+    }
+
+    pub fn get(&self, condition: &C) -> Option<&FreqDist<T>> {
+        self.dists.get(condition)
+    }
+
+    pub fn conditions(&self) -> impl Iterator<Item=&C> {
+        self.dists.keys()
+    }
+
+    /// Lidstone estimate of P(sample | condition). Unseen conditions get a uniform distribution over the bins.
+    pub fn lidstone(&self, condition: &C, sample: &T, gamma: f64, bins: usize) -> f64 {
+        match self.dists.get(condition) {
+            Some(dist) => dist.lidstone(sample, gamma, bins),
+            None => FreqDist::new().lidstone(sample, gamma, bins),
+        }
+    }
+}
+
+impl<C: Hash + Eq, T: Hash + Eq> Default for ConditionalFreqDist<C, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freq_dist() {
+        let dist: FreqDist<&str> = ["a", "b", "a", "c", "a"].into_iter().collect();
+        assert_eq!(dist.get(&"a"), 3);
+        assert_eq!(dist.get(&"d"), 0);
+        assert_eq!(dist.total(), 5);
+        assert_eq!(dist.bins(), 3);
+        assert_eq!(dist.max(), Some(&"a"));
+        assert_eq!(dist.mle(&"a"), 0.6);
+
+        let tied: FreqDist<&str> = ["c", "b", "a", "c", "b", "a"].into_iter().collect();
+        assert_eq!(tied.max(), Some(&"a"));
+    }
+
+    #[test]
+    fn test_lidstone() {
+        let dist: FreqDist<&str> = ["a", "b", "a"].into_iter().collect();
+        assert_eq!(dist.lidstone(&"a", 0.5, 4), 2.5 / 5.0);
+        assert_eq!(dist.lidstone(&"z", 0.5, 4), 0.5 / 5.0);
+        assert_eq!(dist.laplace(&"b", 3), 2.0 / 6.0);
+    }
+
+    #[test]
+    fn test_conditional_freq_dist() {
+        let mut dist = ConditionalFreqDist::new();
+        dist.increment("DT", "NN");
+        dist.increment("DT", "JJ");
+        dist.increment("DT", "NN");
+        assert_eq!(dist.get(&"DT").unwrap().get(&"NN"), 2);
+        assert!(dist.get(&"NN").is_none());
+        assert_eq!(dist.lidstone(&"NN", &"VB", 1.0, 4), 0.25);
+    }
+}
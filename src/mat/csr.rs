@@ -18,6 +18,41 @@ impl<T> CsrMat<T> where T: Numeric {
             data: Vec::new(),
         }
     }
+
+    /// Matrix from rows of (column index, value) pairs, with the column indices in each row in ascending order
+    pub fn from_sparse_rows(rows: Vec<Vec<(usize, T)>>) -> Self {
+        let mut this = Self::new();
+        this.index_pointers.push(0);
+        for row in rows {
+            for (index, value) in row {
+                this.indices.push(index);
+                this.data.push(value);
+            }
+            this.index_pointers.push(this.indices.len());
+        }
+        this
+    }
+
+    /// number of rows
+    pub fn rows(&self) -> usize {
+        self.index_pointers.len().saturating_sub(1)
+    }
+
+    /// the (column index, value) pairs of the stored values in a row. Empty for rows outside the matrix
+    pub fn row(&self, row: usize) -> impl Iterator<Item=(usize, T)> + Clone + '_ {
+        let (start, end) = if row < self.rows() {
+            (self.index_pointers[row], self.index_pointers[row + 1])
+        } else {
+            (0, 0)
+        };
+        self.indices[start..end].iter().copied().zip(self.data[start..end].iter().copied())
+    }
+}
+
+impl<T> Default for CsrMat<T> where T: Numeric {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> Mat<T> for CsrMat<T> where T: Numeric {
@@ -110,4 +145,15 @@ mod test {
         assert_eq!(csr.get(2, 2), 2);
         assert_eq!(csr.shape(), Shape::new(3, 3));
     }
+
+    #[test]
+    fn test_rows() {
+        let csr = CsrMat::from_sparse_rows(vec![vec![(0, 1.0), (3, 2.0)], vec![], vec![(1, 3.0)]]);
+        assert_eq!(csr.rows(), 3);
+        assert_eq!(csr.row(0).collect::<Vec<_>>(), vec![(0, 1.0), (3, 2.0)]);
+        assert_eq!(csr.row(1).count(), 0);
+        assert_eq!(csr.row(5).count(), 0);
+        assert_eq!(csr.get(2, 1), 3.0);
+        assert_eq!(csr.shape(), Shape::new(3, 4));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,312 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::anyhow;
+
+/// Agreement between coders (annotators) that labeled the same items, following Artstein and Poesio (2008),
+/// "Inter-coder agreement for computational linguistics".
+///
+/// The data are (coder, item, label) triples. Agreement between two labels is 1 minus their distance:
+/// [crate::metrics::distance::binary_distance] for nominal labels, or for instance
+/// [crate::metrics::distance::masi_distance] for sets of labels, or the interval distance for ratings.
+/// Expected agreement for the kappa and pi coefficients is always based on label identity.
+///
+/// Coefficients divide by 1 minus the expected agreement, and are NaN if the expected agreement is 1.
+///
+/// ```
+/// use rltk::metrics::agreement::AnnotationTask;
+/// use rltk::metrics::distance::binary_distance;
+///
+/// let data = vec![
+///     ("a", "item1", "yes"), ("b", "item1", "yes"),
+///     ("a", "item2", "no"), ("b", "item2", "yes"),
+///     ("a", "item3", "no"), ("b", "item3", "no"),
+///     ("a", "item4", "yes"), ("b", "item4", "yes"),
+/// ];
+/// let task = AnnotationTask::new(data, binary_distance).unwrap();
+/// assert_eq!(task.average_observed_agreement(), 0.75);
+/// assert_eq!(task.kappa(), 0.5);
+/// ```
+pub struct AnnotationTask<'a, L, D> {
+    coders: Vec<&'a str>,
+    items: Vec<&'a str>,
+    /// the distinct labels, in order of appearance
+    labels: Vec<L>,
+    /// label index per (coder, item) index
+    annotations: HashMap<(usize, usize), usize>,
+    distance: D,
+}
+
+impl<'a, L: PartialEq, D: Fn(&L, &L) -> f64> AnnotationTask<'a, L, D> {
+    /// Fails if a coder labeled an item more than once
+    pub fn new(data: Vec<(&'a str, &'a str, L)>, distance: D) -> anyhow::Result<Self> {
+        let coders: Vec<&str> = data.iter().map(|(coder, _, _)| *coder).collect::<BTreeSet<_>>().into_iter().collect();
+        let items: Vec<&str> = data.iter().map(|(_, item, _)| *item).collect::<BTreeSet<_>>().into_iter().collect();
+        let mut labels: Vec<L> = Vec::new();
+        let mut annotations = HashMap::new();
+        for (coder, item, label) in data {
+            let label = match labels.iter().position(|known| *known == label) {
+                Some(index) => index,
+                None => {
+                    labels.push(label);
+                    labels.len() - 1
+                }
+            };
+            let key = (coders.binary_search(&coder).unwrap(), items.binary_search(&item).unwrap());
+            if annotations.insert(key, label).is_some() {
+                return Err(anyhow!("coder {} labeled item {} more than once", coder, item));
+            }
+        }
+        Ok(Self { coders, items, labels, annotations, distance })
+    }
+
+    pub fn coders(&self) -> &[&'a str] {
+        &self.coders
+    }
+
+    pub fn items(&self) -> &[&'a str] {
+        &self.items
+    }
+
+    fn coder_index(&self, coder: &str) -> usize {
+        self.coders.binary_search(&coder).unwrap_or_else(|_| panic!("unknown coder {}", coder))
+    }
+
+    /// 1 minus the distance between the labels of two coders for an item, None if either did not label it
+    pub fn agreement(&self, coder_a: &str, coder_b: &str, item: &str) -> Option<f64> {
+        let item = self.items.binary_search(&item).ok()?;
+        let label_a = self.annotations.get(&(self.coders.binary_search(&coder_a).ok()?, item))?;
+        let label_b = self.annotations.get(&(self.coders.binary_search(&coder_b).ok()?, item))?;
+        Some(1.0 - (self.distance)(&self.labels[*label_a], &self.labels[*label_b]))
+    }
+
+    /// The mean agreement of two coders, over the items that both labeled
+    /// # Panics
+    /// for an unknown coder
+    pub fn observed_agreement(&self, coder_a: &str, coder_b: &str) -> f64 {
+        let (a, b) = (self.coder_index(coder_a), self.coder_index(coder_b));
+        let agreements: Vec<f64> = (0..self.items.len())
+            .filter_map(|item| {
+                let label_a = self.annotations.get(&(a, item))?;
+                let label_b = self.annotations.get(&(b, item))?;
+                Some(1.0 - (self.distance)(&self.labels[*label_a], &self.labels[*label_b]))
+            })
+            .collect();
+        agreements.iter().sum::<f64>() / agreements.len() as f64
+    }
+
+    /// The observed agreement, averaged over all pairs of coders
+    pub fn average_observed_agreement(&self) -> f64 {
+        self.pairwise_average(|a, b| self.observed_agreement(a, b))
+    }
+
+    /// The agreement expected by chance for two coders, each with their own distribution of labels (Cohen)
+    pub fn expected_agreement_kappa(&self, coder_a: &str, coder_b: &str) -> f64 {
+        let (a, b) = (self.coder_index(coder_a), self.coder_index(coder_b));
+        let (counts_a, counts_b) = (self.label_counts(Some(a)), self.label_counts(Some(b)));
+        let items = self.items.len() as f64;
+        counts_a.iter().zip(&counts_b).map(|(count_a, count_b)| (count_a / items) * (count_b / items)).sum()
+    }
+
+    /// Cohen's kappa for two coders
+    pub fn kappa_pairwise(&self, coder_a: &str, coder_b: &str) -> f64 {
+        let expected = self.expected_agreement_kappa(coder_a, coder_b);
+        (self.observed_agreement(coder_a, coder_b) - expected) / (1.0 - expected)
+    }
+
+    /// Cohen's kappa (1960), averaged over all pairs of coders
+    pub fn kappa(&self) -> f64 {
+        self.pairwise_average(|a, b| self.kappa_pairwise(a, b))
+    }
+
+    /// Multi-kappa (Davies and Fleiss 1982): observed and expected agreement as for kappa, but averaged over all pairs of coders
+    /// before they are combined
+    pub fn multi_kappa(&self) -> f64 {
+        let expected = self.pairwise_average(|a, b| self.expected_agreement_kappa(a, b));
+        (self.average_observed_agreement() - expected) / (1.0 - expected)
+    }
+
+    /// Scott's pi (1955), generalized to any number of coders: chance agreement is based on the distribution of labels
+    /// over all coders together
+    pub fn pi(&self) -> f64 {
+        let total = (self.items.len() * self.coders.len()) as f64;
+        let expected: f64 = self.label_counts(None).iter().map(|count| (count / total).powi(2)).sum();
+        (self.average_observed_agreement() - expected) / (1.0 - expected)
+    }
+
+    /// Fleiss' kappa (1971), which is the same as the generalized [AnnotationTask::pi]
+    pub fn fleiss_kappa(&self) -> f64 {
+        self.pi()
+    }
+
+    /// Krippendorff's alpha (1980): 1 minus the ratio of observed and expected disagreement, using the distance function.
+    /// Items that were labeled by only one coder are left out, so it works with missing labels.
+    /// 1 if only one label was used
+    pub fn alpha(&self) -> f64 {
+        if self.labels.len() < 2 {
+            return 1.0;
+        }
+        let mut all_counts = vec![0.0; self.labels.len()];
+        let mut observed = 0.0;
+        for item in 0..self.items.len() {
+            let mut counts = vec![0.0; self.labels.len()];
+            for coder in 0..self.coders.len() {
+                if let Some(label) = self.annotations.get(&(coder, item)) {
+                    counts[*label] += 1.0;
+                }
+            }
+            let values: f64 = counts.iter().sum();
+            if values < 2.0 {
+                continue;
+            }
+            observed += self.disagreement(&counts) * values;
+            all_counts.iter_mut().zip(&counts).for_each(|(total, count)| *total += count);
+        }
+        let observed = observed / all_counts.iter().sum::<f64>();
+        1.0 - observed / self.disagreement(&all_counts)
+    }
+
+    /// mean distance between all pairs of different values, given the number of values with every label
+    fn disagreement(&self, counts: &[f64]) -> f64 {
+        let total: f64 = counts.iter().sum();
+        let mut pairs = 0.0;
+        for (j, count_j) in counts.iter().enumerate() {
+            for (l, count_l) in counts.iter().enumerate() {
+                pairs += count_j * count_l * (self.distance)(&self.labels[l], &self.labels[j]);
+            }
+        }
+        pairs / (total * (total - 1.0))
+    }
+
+    /// number of items with every label, for one coder or all coders
+    fn label_counts(&self, coder: Option<usize>) -> Vec<f64> {
+        let mut counts = vec![0.0; self.labels.len()];
+        for ((c, _), label) in &self.annotations {
+            if coder.is_none_or(|coder| coder == *c) {
+                counts[*label] += 1.0;
+            }
+        }
+        counts
+    }
+
+    fn pairwise_average(&self, function: impl Fn(&str, &str) -> f64) -> f64 {
+        let mut total = 0.0;
+        let mut pairs = 0;
+        for (index, coder_a) in self.coders.iter().enumerate() {
+            for coder_b in &self.coders[index + 1..] {
+                total += function(coder_a, coder_b);
+                pairs += 1;
+            }
+        }
+        total / pairs as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::metrics::distance::{binary_distance, interval_distance, masi_distance};
+    use crate::test::assert_close;
+
+    /// the dialogue act example from Artstein and Poesio (2008): two coders, 100 items
+    fn artstein_poesio() -> Vec<(&'static str, String, &'static str)> {
+        let cells = [("stat", "stat", 46), ("stat", "ireq", 6), ("ireq", "ireq", 32), ("chck", "ireq", 6), ("chck", "chck", 10)];
+        let mut data = Vec::new();
+        let mut item = 0;
+        for (label_a, label_b, count) in cells {
+            for _ in 0..count {
+                data.push(("a", item.to_string(), label_a));
+                data.push(("b", item.to_string(), label_b));
+                item += 1;
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_artstein_poesio() {
+        let data = artstein_poesio();
+        let task = AnnotationTask::new(data.iter().map(|(c, i, l)| (*c, i.as_str(), *l)).collect(), binary_distance).unwrap();
+        assert_eq!(task.items().len(), 100);
+        assert_close(task.average_observed_agreement(), 0.88, 1e-5);
+        assert_close(task.pi(), 0.79953, 1e-5);
+        assert_close(task.kappa(), (0.88 - 0.396) / 0.604, 1e-5);
+        assert_close(task.multi_kappa(), task.kappa(), 1e-5);
+        assert_close(task.alpha(), 1.0 - 0.12 / (23944.0 / 39800.0), 1e-5);
+    }
+
+    #[test]
+    fn test_fleiss() {
+        // the example from Fleiss (1971) as on Wikipedia: 14 raters put 10 items in 5 categories
+        let counts = [[0, 0, 0, 0, 14], [0, 2, 6, 4, 2], [0, 0, 3, 5, 6], [0, 3, 9, 2, 0], [2, 2, 8, 1, 1],
+            [7, 7, 0, 0, 0], [3, 2, 6, 3, 0], [2, 5, 3, 2, 2], [6, 5, 2, 1, 0], [0, 2, 2, 3, 7]];
+        let coders: Vec<String> = (0..14).map(|coder| format!("c{}", coder)).collect();
+        let items: Vec<String> = (0..10).map(|item| format!("i{}", item)).collect();
+        let mut data = Vec::new();
+        for (item, item_counts) in counts.iter().enumerate() {
+            let mut coder = 0;
+            for (category, count) in item_counts.iter().enumerate() {
+                for _ in 0..*count {
+                    data.push((coders[coder].as_str(), items[item].as_str(), category));
+                    coder += 1;
+                }
+            }
+        }
+        let task = AnnotationTask::new(data, binary_distance).unwrap();
+        assert_close(task.fleiss_kappa(), 0.20993, 1e-5);
+    }
+
+    fn krippendorff() -> Vec<(&'static str, String, f64)> {
+        // Krippendorff (2011), "Computing Krippendorff's alpha-reliability": 4 coders, 12 units, with missing values
+        let values = [
+            ("A", [Some(1.0), Some(2.0), Some(3.0), Some(3.0), Some(2.0), Some(1.0), Some(4.0), Some(1.0), Some(2.0), None, None, None]),
+            ("B", [Some(1.0), Some(2.0), Some(3.0), Some(3.0), Some(2.0), Some(2.0), Some(4.0), Some(1.0), Some(2.0), Some(5.0), None, Some(3.0)]),
+            ("C", [None, Some(3.0), Some(3.0), Some(3.0), Some(2.0), Some(3.0), Some(4.0), Some(2.0), Some(2.0), Some(5.0), Some(1.0), None]),
+            ("D", [Some(1.0), Some(2.0), Some(3.0), Some(3.0), Some(2.0), Some(4.0), Some(4.0), Some(1.0), Some(2.0), Some(5.0), Some(1.0), None]),
+        ];
+        let mut data = Vec::new();
+        for (coder, row) in values {
+            for (unit, value) in row.iter().enumerate() {
+                if let Some(value) = value {
+                    data.push((coder, format!("u{:02}", unit), *value));
+                }
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_krippendorff_alpha() {
+        let data = krippendorff();
+        let nominal = AnnotationTask::new(data.iter().map(|(c, u, v)| (*c, u.as_str(), *v)).collect(), binary_distance).unwrap();
+        assert_close((nominal.alpha() * 1000.0).round() / 1000.0, 0.743, 1e-5);
+        let interval = AnnotationTask::new(data.iter().map(|(c, u, v)| (*c, u.as_str(), *v)).collect(), |a: &f64, b: &f64| interval_distance(*a, *b)).unwrap();
+        assert_close((interval.alpha() * 1000.0).round() / 1000.0, 0.849, 1e-5);
+    }
+
+    #[test]
+    fn test_set_labels() {
+        let data = vec![
+            ("a", "1", HashSet::from(["x"])), ("b", "1", HashSet::from(["x"])),
+            ("a", "2", HashSet::from(["x", "y"])), ("b", "2", HashSet::from(["x"])),
+        ];
+        let task = AnnotationTask::new(data, masi_distance).unwrap();
+        // item 2: 1 - 1/2 * 0.67
+        assert_close(task.agreement("a", "b", "2").unwrap(), 0.335, 1e-5);
+        assert_close(task.observed_agreement("a", "b"), (1.0 + 0.335) / 2.0, 1e-5);
+        assert_eq!(task.agreement("a", "c", "2"), None);
+    }
+
+    #[test]
+    fn test_duplicate_annotation() {
+        assert!(AnnotationTask::new(vec![("a", "1", "x"), ("a", "1", "y")], binary_distance).is_err());
+    }
+
+    #[test]
+    fn test_single_label() {
+        let task = AnnotationTask::new(vec![("a", "1", "x"), ("b", "1", "x")], binary_distance).unwrap();
+        assert_eq!(task.alpha(), 1.0);
+        assert!(task.kappa().is_nan());
+    }
+}
@@ -0,0 +1,251 @@
+/// Added to denominators and logarithms, so that empty cells do not give infinite or undefined scores
+const SMALL: f64 = 1e-20;
+
+/// The counts needed to score how strongly the words of an n-gram are associated, for n from 2 to 4.
+///
+/// Naming follows nltk: in `n_iix`, an `i` is a position that holds the word of the n-gram and an `x` a position that
+/// holds any word. So `n_ii` is the count of the bigram, `n_ix` the count of its first word, `n_xi` of its second word
+/// and `n_xx` the total number of bigrams (the size of the corpus)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marginals {
+    n: usize,
+    /// count per pattern, bit j set if position j holds the word of the n-gram
+    counts: Vec<f64>,
+}
+
+impl Marginals {
+    pub fn bigram(n_ii: usize, n_ix: usize, n_xi: usize, n_xx: usize) -> Self {
+        Self::from_patterns(&[("ii", n_ii as f64), ("ix", n_ix as f64), ("xi", n_xi as f64), ("xx", n_xx as f64)])
+    }
+
+    /// the pair counts are n_iix, n_ixi, n_xii and the word counts n_ixx, n_xix, n_xxi
+    pub fn trigram(n_iii: usize, pairs: [usize; 3], words: [usize; 3], n_xxx: usize) -> Self {
+        Self::from_patterns(&[
+            ("iii", n_iii as f64),
+            ("iix", pairs[0] as f64), ("ixi", pairs[1] as f64), ("xii", pairs[2] as f64),
+            ("ixx", words[0] as f64), ("xix", words[1] as f64), ("xxi", words[2] as f64),
+            ("xxx", n_xxx as f64),
+        ])
+    }
+
+    /// the triple counts are n_iiix, n_iixi, n_ixii, n_xiii, the pair counts n_iixx, n_ixix, n_ixxi, n_xixi, n_xxii, n_xiix
+    /// and the word counts n_ixxx, n_xixx, n_xxix, n_xxxi
+    pub fn quadgram(n_iiii: usize, triples: [usize; 4], pairs: [usize; 6], words: [usize; 4], n_xxxx: usize) -> Self {
+        Self::from_patterns(&[
+            ("iiii", n_iiii as f64),
+            ("iiix", triples[0] as f64), ("iixi", triples[1] as f64), ("ixii", triples[2] as f64), ("xiii", triples[3] as f64),
+            ("iixx", pairs[0] as f64), ("ixix", pairs[1] as f64), ("ixxi", pairs[2] as f64), ("xixi", pairs[3] as f64), ("xxii", pairs[4] as f64), ("xiix", pairs[5] as f64),
+            ("ixxx", words[0] as f64), ("xixx", words[1] as f64), ("xxix", words[2] as f64), ("xxxi", words[3] as f64),
+            ("xxxx", n_xxxx as f64),
+        ])
+    }
+
+    /// The counts per pattern, like `("ix", 3.0)`. Patterns that are not given count 0
+    pub(crate) fn from_patterns(patterns: &[(&str, f64)]) -> Self {
+        let n = patterns[0].0.len();
+        let mut counts = vec![0.0; 1 << n];
+        for (pattern, count) in patterns {
+            let mask = pattern.bytes().enumerate().filter(|(_, c)| *c == b'i').fold(0, |mask, (j, _)| mask | 1 << j);
+            counts[mask] = *count;
+        }
+        Self { n, counts }
+    }
+
+    /// the n of the n-gram
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn ngram_count(&self) -> f64 {
+        self.counts[self.counts.len() - 1]
+    }
+
+    /// the count of every word of the n-gram
+    pub fn word_counts(&self) -> Vec<f64> {
+        (0..self.n).map(|j| self.counts[1 << j]).collect()
+    }
+
+    pub fn total(&self) -> f64 {
+        self.counts[0]
+    }
+
+    /// The number of n-grams for every combination of positions that do or do not hold the word of the n-gram.
+    /// Cell i has bit j set if position j holds another word, so cell 0 is the count of the n-gram, and for bigrams
+    /// the cells are n_ii, n_oi, n_io, n_oo
+    pub fn contingency(&self) -> Vec<f64> {
+        let all = self.counts.len() - 1;
+        (0..self.counts.len())
+            .map(|cell| {
+                // inclusion-exclusion over the patterns that fix at least the positions that hold the word
+                let fixed = all & !cell;
+                (0..self.counts.len())
+                    .filter(|pattern| pattern & fixed == fixed)
+                    .map(|pattern| {
+                        let sign = if (pattern & cell).count_ones() % 2 == 0 { 1.0 } else { -1.0 };
+                        sign * self.counts[pattern]
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// The counts of the contingency cells that are expected if the words occur independently
+    pub fn expected_values(&self) -> Vec<f64> {
+        expected_values(&self.contingency(), self.n)
+    }
+
+    /// The count of the n-gram expected if the words occur independently
+    fn expected_ngram_count(&self) -> f64 {
+        self.word_counts().iter().product::<f64>() / self.total().powi(self.n as i32 - 1)
+    }
+}
+
+fn expected_values(contingency: &[f64], n: usize) -> Vec<f64> {
+    let total: f64 = contingency.iter().sum();
+    (0..contingency.len())
+        .map(|cell| {
+            (0..n)
+                .map(|j| {
+                    let bit = 1 << j;
+                    (0..contingency.len()).filter(|other| other & bit == cell & bit).map(|other| contingency[other]).sum::<f64>()
+                })
+                .product::<f64>()
+                / total.powi(n as i32 - 1)
+        })
+        .collect()
+}
+
+/// The relative frequency of the n-gram
+pub fn raw_freq(marginals: &Marginals) -> f64 {
+    marginals.ngram_count() / marginals.total()
+}
+
+/// Pointwise mutual information: the log (base 2) of how much more often the n-gram occurs than expected.
+/// Favors rare n-grams
+///
+/// ```
+/// use rltk::metrics::association::{pmi, Marginals};
+///
+/// // "Ayatollah Ruhollah" in Manning and Schütze (1999), table 5.14
+/// let marginals = Marginals::bigram(20, 42, 20, 14307668);
+/// assert!((pmi(&marginals) - 18.38).abs() < 0.01);
+/// ```
+pub fn pmi(marginals: &Marginals) -> f64 {
+    (marginals.ngram_count() / marginals.expected_ngram_count()).log2()
+}
+
+/// Student's t-test of the n-gram count against the count expected if the words occur independently
+///
+/// ```
+/// use rltk::metrics::association::{student_t, Marginals};
+///
+/// // "new companies" in Manning and Schütze (1999), section 5.3.1
+/// let marginals = Marginals::bigram(8, 15828, 4675, 14307668);
+/// assert!((student_t(&marginals) - 0.999932).abs() < 1e-6);
+/// ```
+pub fn student_t(marginals: &Marginals) -> f64 {
+    (marginals.ngram_count() - marginals.expected_ngram_count()) / (marginals.ngram_count() + SMALL).sqrt()
+}
+
+/// Pearson's chi-square test over the contingency table
+pub fn chi_sq(marginals: &Marginals) -> f64 {
+    let contingency = marginals.contingency();
+    let expected = expected_values(&contingency, marginals.n);
+    contingency.iter().zip(&expected).map(|(observed, expected)| (observed - expected).powi(2) / (expected + SMALL)).sum()
+}
+
+/// Dunning's log-likelihood ratio over the contingency table, which is better than chi-square for sparse counts
+pub fn likelihood_ratio(marginals: &Marginals) -> f64 {
+    let contingency = marginals.contingency();
+    let expected = expected_values(&contingency, marginals.n);
+    2.0 * contingency.iter().zip(&expected).map(|(observed, expected)| observed * (observed / (expected + SMALL) + SMALL).ln()).sum::<f64>()
+}
+
+/// The Poisson-Stirling measure: n_ii * (log2(n_ii / expected) - 1)
+pub fn poisson_stirling(marginals: &Marginals) -> f64 {
+    let count = marginals.ngram_count();
+    count * ((count / marginals.expected_ngram_count()).log2() - 1.0)
+}
+
+/// The Dice coefficient: n times the n-gram count, divided by the sum of the counts of its words.
+/// For bigrams, 2 * n_ii / (n_ix + n_xi)
+pub fn dice(marginals: &Marginals) -> f64 {
+    marginals.n as f64 * marginals.ngram_count() / marginals.word_counts().iter().sum::<f64>()
+}
+
+/// The Jaccard index: the n-gram count, divided by the number of n-grams that contain at least one of its words in its position
+pub fn jaccard(marginals: &Marginals) -> f64 {
+    let contingency = marginals.contingency();
+    contingency[0] / contingency[..contingency.len() - 1].iter().sum::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::assert_close;
+
+    #[test]
+    fn test_bigram_contingency() {
+        let marginals = Marginals::bigram(2, 5, 3, 20);
+        assert_eq!(marginals.contingency(), vec![2.0, 1.0, 3.0, 14.0]);
+        assert_eq!(marginals.expected_values(), vec![0.75, 2.25, 4.25, 12.75]);
+    }
+
+    #[test]
+    fn test_trigram_contingency() {
+        let marginals = Marginals::trigram(1, [2, 3, 4], [5, 6, 7], 30);
+        // n_iii, n_oii, n_ioi, n_ooi, n_iio, n_oio, n_ioo, n_ooo
+        assert_eq!(marginals.contingency(), vec![1.0, 3.0, 2.0, 1.0, 1.0, 1.0, 1.0, 20.0]);
+        assert_eq!(marginals.contingency().iter().sum::<f64>(), 30.0);
+    }
+
+    #[test]
+    fn test_quadgram_contingency() {
+        let marginals = Marginals::quadgram(1, [1, 1, 1, 1], [1, 1, 1, 1, 1, 1], [1, 1, 1, 1], 10);
+        let mut expected = vec![0.0; 16];
+        expected[0] = 1.0;
+        expected[15] = 9.0;
+        assert_eq!(marginals.contingency(), expected);
+        assert_eq!(marginals.word_counts(), vec![1.0; 4]);
+    }
+
+    #[test]
+    fn test_scores() {
+        // the words only occur together, in half of the bigrams: the contingency is 2, 0, 0, 2
+        let marginals = Marginals::bigram(2, 2, 2, 4);
+        assert_close(chi_sq(&marginals), 4.0, 1e-6);
+        assert_close(likelihood_ratio(&marginals), 8.0 * 2f64.ln(), 1e-6);
+        assert_close(pmi(&marginals), 1.0, 1e-6);
+        assert_close(poisson_stirling(&marginals), 0.0, 1e-6);
+        assert_close(dice(&marginals), 1.0, 1e-6);
+        assert_close(jaccard(&marginals), 1.0, 1e-6);
+        assert_close(raw_freq(&marginals), 0.5, 1e-6);
+        assert_close(student_t(&marginals), 1.0 / 2f64.sqrt(), 1e-6);
+
+        // independent words
+        let marginals = Marginals::bigram(1, 2, 2, 4);
+        assert_close(chi_sq(&marginals), 0.0, 1e-6);
+        assert_close(likelihood_ratio(&marginals), 0.0, 1e-6);
+        assert_close(pmi(&marginals), 0.0, 1e-6);
+        assert_close(dice(&marginals), 0.5, 1e-6);
+        assert_close(jaccard(&marginals), 1.0 / 3.0, 1e-6);
+    }
+
+    #[test]
+    fn test_chi_sq_new_companies() {
+        // Manning and Schütze (1999), section 5.3.3
+        let marginals = Marginals::bigram(8, 15828, 4675, 14307668);
+        assert!((chi_sq(&marginals) - 1.55).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_trigram_scores() {
+        let marginals = Marginals::trigram(2, [2, 2, 2], [2, 2, 2], 8);
+        // expected 2 * 2 * 2 / 8^2 times
+        assert_close(pmi(&marginals), 4.0, 1e-6);
+        assert_close(dice(&marginals), 1.0, 1e-6);
+        assert_close(jaccard(&marginals), 1.0, 1e-6);
+        assert!(chi_sq(&marginals) > 0.0);
+        assert!(likelihood_ratio(&marginals) > 0.0);
+    }
+}
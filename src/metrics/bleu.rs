@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use crate::util::ngrams;
+
+/// The smoothing methods of Chen and Cherry (2014), "A Systematic Comparison of Smoothing Techniques for Sentence-Level BLEU",
+/// for n-gram precisions that are 0. They follow nltk's `SmoothingFunction`, where the default epsilon is 0.1 and
+/// the default k and alpha are 5
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Smoothing {
+    /// Method 0: no smoothing. A precision of 0 is replaced by the smallest positive number, so the score is practically 0
+    #[default]
+    None,
+    /// Method 1: add epsilon to the numerator of precisions that are 0
+    Epsilon(f64),
+    /// Method 2: add 1 to the numerator and denominator of every precision except the unigram one (Lin and Och 2004)
+    AddOne,
+    /// Method 3: the NIST geometric sequence smoothing: the k-th precision that is 0 becomes 1 / (2^k * denominator)
+    Nist,
+    /// Method 4: like method 3, but shorter hypotheses get smaller values: 1 / (2^k * k_factor / ln(hypothesis length)),
+    /// divided by the denominator
+    LengthScaled(f64),
+    /// Method 5: every precision is the mean of itself and its neighbours, the next one of the last being the 5-gram precision
+    Average,
+    /// Method 6: interpolates every precision from the trigram on with a prior from the two before, with weight alpha
+    /// (Gao and He 2013)
+    Interpolated(f64),
+    /// Method 7: method 4 followed by method 5
+    LengthScaledAverage(f64),
+}
+
+/// The clipped n-gram precision of a hypothesis as (numerator, denominator): the number of n-grams of the hypothesis
+/// that occur in a reference, counting each n-gram at most as often as it occurs in a single reference,
+/// and the number of n-grams of the hypothesis (at least 1)
+///
+/// ```
+/// use rltk::metrics::bleu::modified_precision;
+///
+/// let references = [vec!["the", "cat", "is", "on", "the", "mat"], vec!["there", "is", "a", "cat", "on", "the", "mat"]];
+/// let hypothesis = ["the"; 7];
+/// assert_eq!(modified_precision(&references, &hypothesis, 1), (2, 7));
+/// ```
+pub fn modified_precision<'a, R: AsRef<[&'a str]>>(references: &[R], hypothesis: &[&'a str], n: usize) -> (usize, usize) {
+    let counts = ngram_counts(hypothesis, n);
+    let mut max_counts: HashMap<Vec<&str>, usize> = HashMap::new();
+    for reference in references {
+        let reference_counts = ngram_counts(reference.as_ref(), n);
+        for ngram in counts.keys() {
+            let count = reference_counts.get(ngram).copied().unwrap_or(0);
+            let max_count = max_counts.entry(ngram.clone()).or_insert(0);
+            *max_count = (*max_count).max(count);
+        }
+    }
+    let numerator = counts.iter().map(|(ngram, count)| (*count).min(max_counts[ngram])).sum();
+    let denominator = counts.values().sum::<usize>().max(1);
+    (numerator, denominator)
+}
+
+/// The length of the reference closest to the hypothesis length, the shortest one on a tie
+pub fn closest_ref_length<R: AsRef<[S]>, S>(references: &[R], hypothesis_length: usize) -> usize {
+    references.iter()
+        .map(|reference| reference.as_ref().len())
+        .min_by_key(|length| (length.abs_diff(hypothesis_length), *length))
+        .unwrap_or(0)
+}
+
+/// 1 if the hypothesis is longer than the reference, exp(1 - reference length / hypothesis length) otherwise,
+/// and 0 for an empty hypothesis
+pub fn brevity_penalty(closest_ref_length: usize, hypothesis_length: usize) -> f64 {
+    if hypothesis_length > closest_ref_length {
+        1.0
+    } else if hypothesis_length == 0 {
+        0.0
+    } else {
+        (1.0 - closest_ref_length as f64 / hypothesis_length as f64).exp()
+    }
+}
+
+/// The BLEU score of a hypothesis against one or more references: the brevity penalty times the weighted geometric mean
+/// of the modified n-gram precisions, for n from 1 to the number of weights. The usual weights are 0.25 for 1- to 4-grams.
+/// Equal to [corpus_bleu] with one hypothesis
+///
+/// ```
+/// use rltk::metrics::bleu::{sentence_bleu, Smoothing};
+///
+/// let reference = "the cat is on the mat".split(' ').collect::<Vec<_>>();
+/// let hypothesis = "the cat sat on the mat".split(' ').collect::<Vec<_>>();
+/// // there are no matching 4-grams
+/// assert!(sentence_bleu(&[&reference], &hypothesis, &[0.25; 4], Smoothing::None) < 1e-10);
+/// // the geometric mean of 5/6, 3/5, 1/4 and 0.1/3
+/// let bleu = sentence_bleu(&[&reference], &hypothesis, &[0.25; 4], Smoothing::Epsilon(0.1));
+/// assert!((bleu - 0.2541).abs() < 1e-4);
+/// ```
+pub fn sentence_bleu<'a, R: AsRef<[&'a str]>>(references: &[R], hypothesis: &[&'a str], weights: &[f64], smoothing: Smoothing) -> f64 {
+    corpus_bleu(&[references], &[hypothesis], weights, smoothing)
+}
+
+/// The BLEU score of a corpus of hypotheses, each with its own references (Papineni et al. 2002).
+/// The n-gram matches and the lengths are summed over the corpus before the precisions and the brevity penalty are computed,
+/// so this is not the mean of the sentence scores. Returns 0 if no unigram matches.
+/// Smoothing methods that look at the hypothesis, use the last one, like nltk
+/// # Panics
+/// if there are no weights, or if the number of hypotheses differs from the number of lists of references
+pub fn corpus_bleu<'a, Rs: AsRef<[R]>, R: AsRef<[&'a str]>, H: AsRef<[&'a str]>>(list_of_references: &[Rs], hypotheses: &[H], weights: &[f64], smoothing: Smoothing) -> f64 {
+    assert!(!weights.is_empty(), "there should be at least one weight");
+    assert_eq!(list_of_references.len(), hypotheses.len(), "there should be references for every hypothesis");
+    let mut numerators = vec![0; weights.len()];
+    let mut denominators = vec![0; weights.len()];
+    let (mut hypothesis_length, mut reference_length) = (0, 0);
+    for (references, hypothesis) in list_of_references.iter().zip(hypotheses) {
+        let (references, hypothesis) = (references.as_ref(), hypothesis.as_ref());
+        for n in 1..=weights.len() {
+            let (numerator, denominator) = modified_precision(references, hypothesis, n);
+            numerators[n - 1] += numerator;
+            denominators[n - 1] += denominator;
+        }
+        hypothesis_length += hypothesis.len();
+        reference_length += closest_ref_length(references, hypothesis.len());
+    }
+    if numerators[0] == 0 {
+        return 0.0;
+    }
+
+    let (references, hypothesis) = match (list_of_references.last(), hypotheses.last()) {
+        (Some(references), Some(hypothesis)) => (references.as_ref(), hypothesis.as_ref()),
+        _ => return 0.0,
+    };
+    let precisions = smooth(smoothing, &numerators, &denominators, references, hypothesis, hypothesis_length);
+    let log_mean: f64 = weights.iter().zip(&precisions).map(|(weight, precision)| weight * precision.ln()).sum();
+    brevity_penalty(reference_length, hypothesis_length) * log_mean.exp()
+}
+
+/// The smoothed precisions, from the summed numerators and denominators
+fn smooth<'a, R: AsRef<[&'a str]>>(smoothing: Smoothing, numerators: &[usize], denominators: &[usize], references: &[R], hypothesis: &[&'a str], hypothesis_length: usize) -> Vec<f64> {
+    let precisions: Vec<f64> = numerators.iter().zip(denominators).map(|(n, d)| *n as f64 / *d as f64).collect();
+    match smoothing {
+        Smoothing::None => precisions.iter().map(|p| if *p == 0.0 { f64::MIN_POSITIVE } else { *p }).collect(),
+        Smoothing::Epsilon(epsilon) => numerators.iter().zip(denominators).zip(&precisions)
+            .map(|((n, d), p)| if *n == 0 { epsilon / *d as f64 } else { *p })
+            .collect(),
+        Smoothing::AddOne => numerators.iter().zip(denominators).enumerate()
+            .map(|(i, (n, d))| if i == 0 { precisions[0] } else { (n + 1) as f64 / (d + 1) as f64 })
+            .collect(),
+        Smoothing::Nist => {
+            let mut zeros = 0;
+            numerators.iter().zip(denominators).zip(&precisions)
+                .map(|((n, d), p)| {
+                    if *n == 0 {
+                        zeros += 1;
+                        1.0 / (2f64.powi(zeros) * *d as f64)
+                    } else {
+                        *p
+                    }
+                })
+                .collect()
+        }
+        Smoothing::LengthScaled(k) => length_scaled(k, numerators, denominators, precisions, hypothesis_length),
+        Smoothing::Average => average(precisions, references, hypothesis),
+        Smoothing::Interpolated(alpha) => {
+            let mut precisions = precisions;
+            for i in 2..precisions.len() {
+                let prior = if precisions[i - 2] == 0.0 { 0.0 } else { precisions[i - 1].powi(2) / precisions[i - 2] };
+                let hypothesis_ngrams = hypothesis.len().saturating_sub(i);
+                precisions[i] = (numerators[i] as f64 + alpha * prior) / (hypothesis_ngrams as f64 + alpha);
+            }
+            precisions
+        }
+        Smoothing::LengthScaledAverage(k) => {
+            let precisions = length_scaled(k, numerators, denominators, precisions, hypothesis_length);
+            average(precisions, references, hypothesis)
+        }
+    }
+}
+
+fn length_scaled(k: f64, numerators: &[usize], denominators: &[usize], mut precisions: Vec<f64>, hypothesis_length: usize) -> Vec<f64> {
+    let mut zeros = 0;
+    for (i, precision) in precisions.iter_mut().enumerate() {
+        if numerators[i] == 0 && hypothesis_length > 1 {
+            zeros += 1;
+            let numerator = 1.0 / (2f64.powi(zeros) * k / (hypothesis_length as f64).ln());
+            *precision = numerator / denominators[i] as f64;
+        }
+    }
+    precisions
+}
+
+fn average<'a, R: AsRef<[&'a str]>>(precisions: Vec<f64>, references: &[R], hypothesis: &[&'a str]) -> Vec<f64> {
+    // nltk always takes the 5-gram precision as the one after the last
+    let (numerator, denominator) = modified_precision(references, hypothesis, 5);
+    let mut next = precisions.clone();
+    next.push(numerator as f64 / denominator as f64);
+    let mut previous = precisions[0] + 1.0;
+    let mut averaged = Vec::with_capacity(precisions.len());
+    for (i, precision) in precisions.iter().enumerate() {
+        previous = (previous + precision + next[i + 1]) / 3.0;
+        averaged.push(previous);
+    }
+    averaged
+}
+
+fn ngram_counts<'a>(tokens: &'a [&'a str], n: usize) -> HashMap<Vec<&'a str>, usize> {
+    let mut counts = HashMap::new();
+    for ngram in ngrams(tokens.iter(), n) {
+        *counts.entry(ngram.copied().collect()).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::assert_close;
+
+    const HYPOTHESIS1: [&str; 18] = ["It", "is", "a", "guide", "to", "action", "which", "ensures", "that", "the", "military",
+        "always", "obeys", "the", "commands", "of", "the", "party"];
+    const HYPOTHESIS2: [&str; 14] = ["It", "is", "to", "insure", "the", "troops", "forever", "hearing", "the", "activity",
+        "guidebook", "that", "party", "direct"];
+    const REFERENCE1: [&str; 16] = ["It", "is", "a", "guide", "to", "action", "that", "ensures", "that", "the", "military",
+        "will", "forever", "heed", "Party", "commands"];
+    const REFERENCE2: [&str; 18] = ["It", "is", "the", "guiding", "principle", "which", "guarantees", "the", "military",
+        "forces", "always", "being", "under", "the", "command", "of", "the", "Party"];
+    const REFERENCE3: [&str; 16] = ["It", "is", "the", "practical", "guide", "for", "the", "army", "always", "to", "heed",
+        "the", "directions", "of", "the", "party"];
+
+    fn references() -> Vec<&'static [&'static str]> {
+        vec![&REFERENCE1, &REFERENCE2, &REFERENCE3]
+    }
+
+    // the expected values are those of nltk's documentation
+
+    #[test]
+    fn test_modified_precision() {
+        let (numerator, denominator) = modified_precision(&references(), &HYPOTHESIS1, 1);
+        assert_eq!((numerator, denominator), (17, 18));
+        let (numerator, denominator) = modified_precision(&references(), &HYPOTHESIS1, 2);
+        assert_eq!((numerator, denominator), (10, 17));
+        let (numerator, denominator) = modified_precision(&references(), &HYPOTHESIS2, 1);
+        assert_eq!((numerator, denominator), (8, 14));
+        let (numerator, denominator) = modified_precision(&references(), &HYPOTHESIS2, 2);
+        assert_eq!((numerator, denominator), (1, 13));
+    }
+
+    #[test]
+    fn test_brevity_penalty() {
+        let references = [vec!["a"; 11], vec!["a"; 8]];
+        assert_eq!(closest_ref_length(&references, 7), 8);
+        assert_close(brevity_penalty(8, 7), 0.8669, 1e-4);
+        let references = [vec!["a"; 11], vec!["a"; 8], vec!["a"; 6], vec!["a"; 7]];
+        assert_eq!(closest_ref_length(&references, 7), 7);
+        assert_eq!(brevity_penalty(7, 7), 1.0);
+        // the shortest of the equally close references
+        assert_eq!(closest_ref_length(&[vec!["a"; 6], vec!["a"; 8]], 7), 6);
+    }
+
+    #[test]
+    fn test_sentence_bleu() {
+        assert_close(sentence_bleu(&references(), &HYPOTHESIS1, &[0.25; 4], Smoothing::None), 0.5046, 1e-4);
+        assert_eq!(sentence_bleu(&references(), &HYPOTHESIS1[..0], &[0.25; 4], Smoothing::None), 0.0);
+        assert_eq!(sentence_bleu(&references(), &HYPOTHESIS1, &[1.0], Smoothing::None), 17.0 / 18.0);
+    }
+
+    #[test]
+    fn test_corpus_bleu() {
+        let hypothesis2 = ["he", "read", "the", "book", "because", "he", "was", "interested", "in", "world", "history"];
+        let reference2 = ["he", "was", "interested", "in", "world", "history", "because", "he", "read", "the", "book"];
+        let list_of_references = [references(), vec![&reference2]];
+        let hypotheses: [&[&str]; 2] = [&HYPOTHESIS1, &hypothesis2];
+        assert_close(corpus_bleu(&list_of_references, &hypotheses, &[0.25; 4], Smoothing::None), 0.5920, 1e-4);
+        let sentences: f64 = list_of_references.iter().zip(&hypotheses)
+            .map(|(references, hypothesis)| sentence_bleu(references, hypothesis, &[0.25; 4], Smoothing::None))
+            .sum();
+        assert_close(sentences / 2.0, 0.6223, 1e-4);
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let references = [&REFERENCE1];
+        let bleu = |smoothing| sentence_bleu(&references, &HYPOTHESIS1, &[0.25; 4], smoothing);
+        assert_close(bleu(Smoothing::None), 0.4118, 1e-4);
+        assert_close(bleu(Smoothing::Epsilon(0.1)), 0.4118, 1e-4);
+        assert_close(bleu(Smoothing::AddOne), 0.4452, 1e-4);
+        assert_close(bleu(Smoothing::Nist), 0.4118, 1e-4);
+        assert_close(bleu(Smoothing::LengthScaled(5.0)), 0.4118, 1e-4);
+        assert_close(bleu(Smoothing::Average), 0.4905, 1e-4);
+        assert_close(bleu(Smoothing::Interpolated(5.0)), 0.4135, 1e-4);
+        assert_close(bleu(Smoothing::LengthScaledAverage(5.0)), 0.4905, 1e-4);
+    }
+
+    #[test]
+    fn test_short_hypothesis() {
+        let references = [["John", "loves", "Mary", "?"]];
+        let hypothesis = ["John", "loves", "Mary"];
+        assert!(sentence_bleu(&references, &hypothesis, &[0.25; 4], Smoothing::None) < 1e-4);
+        assert_close(sentence_bleu(&references, &hypothesis, &[1.0 / 3.0; 3], Smoothing::None), (-1.0f64 / 3.0).exp(), 1e-4);
+    }
+}
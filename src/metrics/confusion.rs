@@ -0,0 +1,247 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::metrics::scores::{ratio, weighted_harmonic_mean};
+
+/// Counts how often every reference (gold) label was predicted as each of the labels
+///
+/// ```
+/// use rltk::metrics::confusion::ConfusionMatrix;
+///
+/// let reference = ["DT", "NN", "VB", "NN"];
+/// let test = ["DT", "NN", "NN", "NN"];
+/// let matrix = ConfusionMatrix::new(&reference, &test);
+/// assert_eq!(matrix.get("VB", "NN"), 1);
+/// assert_eq!(matrix.accuracy(), 0.75);
+/// assert_eq!(matrix.precision("NN"), Some(2.0 / 3.0));
+/// assert_eq!(matrix.recall("NN"), Some(1.0));
+/// print!("{}", matrix);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConfusionMatrix {
+    labels: Vec<String>,
+    indices: HashMap<String, usize>,
+    // rows are reference labels, columns test labels
+    counts: Vec<Vec<usize>>,
+    total: usize,
+}
+
+impl ConfusionMatrix {
+    /// reference and test are the gold and predicted labels for the same items, in the same order
+    /// # Panics
+    /// if reference and test have different lengths
+    pub fn new<R: AsRef<str>, T: AsRef<str>>(reference: &[R], test: &[T]) -> Self {
+        assert_eq!(reference.len(), test.len(), "reference and test should have the same length");
+        let labels: BTreeSet<&str> = reference.iter().map(|label| label.as_ref())
+            .chain(test.iter().map(|label| label.as_ref()))
+            .collect();
+        let labels: Vec<String> = labels.into_iter().map(|label| label.to_string()).collect();
+        let indices: HashMap<String, usize> = labels.iter().enumerate().map(|(i, label)| (label.clone(), i)).collect();
+
+        let mut counts = vec![vec![0; labels.len()]; labels.len()];
+        for (r, t) in reference.iter().zip(test.iter()) {
+            counts[indices[r.as_ref()]][indices[t.as_ref()]] += 1;
+        }
+        Self { labels, indices, counts, total: reference.len() }
+    }
+
+    /// all labels that occur in reference or test, sorted
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// the number of items with the reference label, that were labeled as test
+    pub fn get(&self, reference: &str, test: &str) -> usize {
+        match (self.indices.get(reference), self.indices.get(test)) {
+            (Some(r), Some(t)) => self.counts[*r][*t],
+            _ => 0,
+        }
+    }
+
+    /// number of items
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// fraction of the items that were labeled correctly
+    pub fn accuracy(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        let correct: usize = (0..self.labels.len()).map(|i| self.counts[i][i]).sum();
+        correct as f64 / self.total as f64
+    }
+
+    pub fn true_positives(&self, label: &str) -> usize {
+        self.get(label, label)
+    }
+
+    /// items labeled as `label`, that have another reference label
+    pub fn false_positives(&self, label: &str) -> usize {
+        match self.indices.get(label) {
+            Some(t) => (0..self.labels.len()).filter(|r| r != t).map(|r| self.counts[r][*t]).sum(),
+            None => 0,
+        }
+    }
+
+    /// items with reference label `label`, that were labeled otherwise
+    pub fn false_negatives(&self, label: &str) -> usize {
+        match self.indices.get(label) {
+            Some(r) => (0..self.labels.len()).filter(|t| t != r).map(|t| self.counts[*r][t]).sum(),
+            None => 0,
+        }
+    }
+
+    /// tp / (tp + fp), None if the label was never predicted
+    pub fn precision(&self, label: &str) -> Option<f64> {
+        let tp = self.true_positives(label);
+        ratio(tp, tp + self.false_positives(label))
+    }
+
+    /// tp / (tp + fn), None if the label does not occur in the reference
+    pub fn recall(&self, label: &str) -> Option<f64> {
+        let tp = self.true_positives(label);
+        ratio(tp, tp + self.false_negatives(label))
+    }
+
+    /// harmonic mean of precision and recall (F1), None if either is undefined
+    pub fn f_measure(&self, label: &str) -> Option<f64> {
+        Some(weighted_harmonic_mean(self.precision(label)?, self.recall(label)?, 0.5))
+    }
+
+    /// Unweighted mean of the precision, recall and F1 of every label. Undefined scores count as 0
+    pub fn macro_average(&self) -> AveragedScores {
+        if self.labels.is_empty() {
+            return AveragedScores { precision: 0.0, recall: 0.0, f_measure: 0.0 };
+        }
+        let mean = |score: &dyn Fn(&str) -> Option<f64>| {
+            self.labels.iter().map(|label| score(label).unwrap_or(0.0)).sum::<f64>() / self.labels.len() as f64
+        };
+        AveragedScores {
+            precision: mean(&|label| self.precision(label)),
+            recall: mean(&|label| self.recall(label)),
+            f_measure: mean(&|label| self.f_measure(label)),
+        }
+    }
+
+    /// Precision, recall and F1 over the true positives, false positives and false negatives of all labels together.
+    /// As every item has exactly one reference and one test label, these all equal the accuracy
+    pub fn micro_average(&self) -> AveragedScores {
+        let tp: usize = self.labels.iter().map(|label| self.true_positives(label)).sum();
+        let fp: usize = self.labels.iter().map(|label| self.false_positives(label)).sum();
+        let fn_: usize = self.labels.iter().map(|label| self.false_negatives(label)).sum();
+        let precision = ratio(tp, tp + fp).unwrap_or(0.0);
+        let recall = ratio(tp, tp + fn_).unwrap_or(0.0);
+        AveragedScores { precision, recall, f_measure: weighted_harmonic_mean(precision, recall, 0.5) }
+    }
+
+    /// The matrix as a table, with the reference labels as rows and the test labels as columns.
+    /// Correct counts, on the diagonal, are marked with angle brackets and zeroes are shown as a dot.
+    /// With `show_percents` the cells are percentages of the total number of items
+    pub fn pretty_format(&self, show_percents: bool) -> String {
+        let cell = |count: usize| match count {
+            0 => ".".to_string(),
+            _ if show_percents => format!("{:.1}%", 100.0 * count as f64 / self.total as f64),
+            _ => count.to_string(),
+        };
+        let label_width = self.labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+        let cell_width = self.counts.iter().flatten().map(|count| cell(*count).len())
+            .chain(self.labels.iter().map(|label| label.chars().count()))
+            .max().unwrap_or(1);
+        let separator = format!("{}+{}+\n", "-".repeat(label_width + 1), "-".repeat(self.labels.len() * (cell_width + 2)));
+
+        let mut table = format!("{:>width$} |", "", width = label_width);
+        for label in &self.labels {
+            table.push_str(&format!(" {:>width$} ", label, width = cell_width));
+        }
+        table.push_str("|\n");
+        table.push_str(&separator);
+        for (r, label) in self.labels.iter().enumerate() {
+            table.push_str(&format!("{:>width$} |", label, width = label_width));
+            for (t, count) in self.counts[r].iter().enumerate() {
+                if r == t {
+                    table.push_str(&format!("<{:>width$}>", cell(*count), width = cell_width));
+                } else {
+                    table.push_str(&format!(" {:>width$} ", cell(*count), width = cell_width));
+                }
+            }
+            table.push_str("|\n");
+        }
+        table.push_str(&separator);
+        table.push_str("(row = reference; col = test)\n");
+        table
+    }
+}
+
+/// Precision, recall and F1, averaged over labels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AveragedScores {
+    pub precision: f64,
+    pub recall: f64,
+    pub f_measure: f64,
+}
+
+/// the counts as a table, see [ConfusionMatrix::pretty_format]
+impl fmt::Display for ConfusionMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pretty_format(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        let reference = ["a", "a", "b", "c", "c", "c"];
+        let test = ["a", "b", "b", "c", "a", "c"];
+        let matrix = ConfusionMatrix::new(&reference, &test);
+        assert_eq!(matrix.labels(), ["a", "b", "c"]);
+        assert_eq!(matrix.get("a", "a"), 1);
+        assert_eq!(matrix.get("c", "a"), 1);
+        assert_eq!(matrix.get("x", "a"), 0);
+        assert_eq!(matrix.false_positives("a"), 1);
+        assert_eq!(matrix.false_negatives("c"), 1);
+        assert_eq!(matrix.accuracy(), 4.0 / 6.0);
+    }
+
+    #[test]
+    fn test_undefined_precision() {
+        let matrix = ConfusionMatrix::new(&["a", "b"], &["a", "a"]);
+        assert_eq!(matrix.precision("b"), None);
+        assert_eq!(matrix.recall("b"), Some(0.0));
+        assert_eq!(matrix.precision("a"), Some(0.5));
+        assert_eq!(matrix.f_measure("b"), None);
+        assert_eq!(matrix.f_measure("a"), Some(2.0 / 3.0));
+    }
+
+    #[test]
+    fn test_averages() {
+        let matrix = ConfusionMatrix::new(&["a", "a", "b", "c"], &["a", "b", "b", "b"]);
+        // precision a 1, b 1/3, c undefined; recall a 1/2, b 1, c 0
+        let averages = matrix.macro_average();
+        assert!((averages.precision - 4.0 / 9.0).abs() < 1e-12);
+        assert!((averages.recall - 0.5).abs() < 1e-12);
+        assert!((averages.f_measure - (2.0 / 3.0 + 0.5) / 3.0).abs() < 1e-12);
+        let micro = matrix.micro_average();
+        assert_eq!(micro, AveragedScores { precision: 0.5, recall: 0.5, f_measure: 0.5 });
+    }
+
+    #[test]
+    fn test_pretty_format() {
+        let reference = ["a", "a", "b", "c", "c", "c"];
+        let test = ["a", "b", "b", "c", "a", "c"];
+        let matrix = ConfusionMatrix::new(&reference, &test);
+        let expected = "  | a  b  c |
+--+---------+
+a |<1> 1  . |
+b | . <1> . |
+c | 1  . <2>|
+--+---------+
+(row = reference; col = test)
+";
+        assert_eq!(matrix.to_string(), expected);
+        assert!(matrix.pretty_format(true).contains("<33.3%>"));
+    }
+}
@@ -1,50 +1,474 @@
-// sandbox, to be removed
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 
 use unicode_segmentation::UnicodeSegmentation;
 
+/// What a string consists of, for comparing strings: the units that are inserted, deleted or substituted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Unit {
+    Bytes,
+    /// unicode scalar values
+    Chars,
+    /// extended grapheme clusters: what a reader sees as a character, like an accented letter or an emoji with a skin tone
+    #[default]
+    Graphemes,
+    /// whitespace separated tokens
+    Tokens,
+}
+
+impl Unit {
+    /// the units of a string, as the bytes that encode them
+    pub(crate) fn split(self, s: &str) -> Vec<&[u8]> {
+        match self {
+            Unit::Bytes => s.as_bytes().chunks(1).collect(),
+            Unit::Chars => s.char_indices().map(|(index, c)| &s.as_bytes()[index..index + c.len_utf8()]).collect(),
+            Unit::Graphemes => s.graphemes(true).map(str::as_bytes).collect(),
+            Unit::Tokens => s.split_whitespace().map(str::as_bytes).collect(),
+        }
+    }
+}
+
+/// The cost of each edit operation, for [crate::metrics::edit_distance_with_costs]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EditCosts {
+    pub insertion: usize,
+    pub deletion: usize,
+    pub substitution: usize,
+    /// cost of swapping two adjacent units, None to not allow transpositions
+    pub transposition: Option<usize>,
+}
+
+impl EditCosts {
+    /// unit costs for insertion, deletion and substitution, no transpositions
+    pub fn levenshtein() -> Self {
+        Self { insertion: 1, deletion: 1, substitution: 1, transposition: None }
+    }
+
+    /// unit costs for all operations, including transpositions
+    pub fn damerau_levenshtein() -> Self {
+        Self { transposition: Some(1), ..Self::levenshtein() }
+    }
+}
+
+impl Default for EditCosts {
+    fn default() -> Self {
+        Self::levenshtein()
+    }
+}
+
+/// An operation in an alignment of two sequences, see [crate::metrics::edit_distance_align]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditOperation {
+    Match,
+    Substitute,
+    Insert,
+    Delete,
+    Transpose,
+}
+
+/// An operation, with the positions in the source (s1) and target (s2) units it applies to.
+///
+/// * match and substitute: `source` and `target` are the units that are compared
+/// * delete: `source` is the deleted unit, `target` the position in the target where it would have been
+/// * insert: `target` is the inserted unit, `source` the position in the source where it is inserted
+/// * transpose: `source` and `target` are the first units of the transposed pair.
+///   The second unit of the pair in the source is the last one before the next operation,
+///   after any deletions and insertions of units between the pair
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edit {
+    pub operation: EditOperation,
+    pub source: usize,
+    pub target: usize,
+}
+
+impl Edit {
+    fn new(operation: EditOperation, source: usize, target: usize) -> Self {
+        Self { operation, source, target }
+    }
+}
+
 pub(crate) struct Element {
     pub(crate) value: usize,
+    /// how this cell was reached: the operation and the cell it was applied to. None for the top left cell
+    pub(crate) step: Option<(EditOperation, usize, usize)>,
 }
 
 impl Element {
-    fn new() -> Self {
+    fn new(value: usize, step: Option<(EditOperation, usize, usize)>) -> Self {
         Self {
-            value: 0,
+            value,
+            step,
         }
     }
 }
 
 // non recursive implementation requires a table
 // my guess is that this is more efficient (should check)
-pub(crate) fn get_edit_distance_table(word1: &str, word2: &str) -> Vec<Vec<Element>> {
-    // create table
-    let mut table = Vec::new();
-    for _ in 0..=word1.len() {
-        let mut row = Vec::new();
-        for _ in 0..=word2.len() {
-            row.push(Element::new())
-        }
+//
+// With transpositions this is the (unrestricted) Damerau-Levenshtein distance: the units between a transposed pair
+// may be edited as well, as in nltk.
+// On ties, steps are preferred in the order match or substitution, deletion, insertion, transposition.
+pub(crate) fn get_edit_distance_table<T: Eq + Hash>(word1: &[T], word2: &[T], costs: &EditCosts) -> Vec<Vec<Element>> {
+    // create table, with the boundaries set
+    let mut table: Vec<Vec<Element>> = Vec::with_capacity(word1.len() + 1);
+    for i1 in 0..=word1.len() {
+        let row = (0..=word2.len())
+            .map(|i2| match (i1, i2) {
+                (0, 0) => Element::new(0, None),
+                (0, _) => Element::new(i2 * costs.insertion, Some((EditOperation::Insert, 0, i2 - 1))),
+                (_, 0) => Element::new(i1 * costs.deletion, Some((EditOperation::Delete, i1 - 1, 0))),
+                _ => Element::new(0, None),
+            })
+            .collect();
         table.push(row);
     }
 
-    // set the boundaries
-    for i in 0..=word1.len() {
-        table[i][0].value = i;
+    // last row in which every unit of word1 occurred
+    let mut last_row: HashMap<&T, usize> = HashMap::new();
+    for (i1, g1) in word1.iter().enumerate() {
+        // last column in this row with a match
+        let mut last_match_column = 0;
+        for (i2, g2) in word2.iter().enumerate() {
+            let d_sub = table[i1][i2].value + (if g1 == g2 { 0 } else { costs.substitution }); // substitution
+            let d_del = table[i1][i2 + 1].value + costs.deletion; //deletion
+            let d_ins = table[i1 + 1][i2].value + costs.insertion; //insertion
+            let diagonal = if g1 == g2 { EditOperation::Match } else { EditOperation::Substitute };
+            let mut best = (d_sub, (diagonal, i1, i2));
+            if d_del < best.0 {
+                best = (d_del, (EditOperation::Delete, i1, i2 + 1));
+            }
+            if d_ins < best.0 {
+                best = (d_ins, (EditOperation::Insert, i1 + 1, i2));
+            }
+
+            if let Some(transposition) = costs.transposition {
+                // g2 earlier in word1 and g1 earlier in word2: delete what is between them, swap, and insert what is between
+                if let Some(&k) = last_row.get(g2) {
+                    let l = last_match_column;
+                    if k > 0 && l > 0 {
+                        let d_trans = table[k - 1][l - 1].value + (i1 - k) * costs.deletion + transposition + (i2 - l) * costs.insertion;
+                        if d_trans < best.0 {
+                            best = (d_trans, (EditOperation::Transpose, k - 1, l - 1));
+                        }
+                    }
+                }
+            }
+            if g1 == g2 {
+                last_match_column = i2 + 1;
+            }
+            table[i1 + 1][i2 + 1] = Element::new(best.0, Some(best.1));
+        }
+        last_row.insert(g1, i1 + 1);
+    }
+    table
+}
+
+/// Follows the steps back from the bottom right cell of the table, and returns the operations in order
+pub(crate) fn backtrace(table: &[Vec<Element>]) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let (mut i1, mut i2) = (table.len() - 1, table[0].len() - 1);
+    while let Some((operation, p1, p2)) = table[i1][i2].step {
+        match operation {
+            EditOperation::Transpose => {
+                // the reverse of: transpose the first units of the pair, delete the units between in the source,
+                // insert the units between in the target
+                for target in (p2 + 1..i2 - 1).rev() {
+                    edits.push(Edit::new(EditOperation::Insert, i1 - 1, target));
+                }
+                for source in (p1 + 1..i1 - 1).rev() {
+                    edits.push(Edit::new(EditOperation::Delete, source, p2 + 1));
+                }
+                edits.push(Edit::new(EditOperation::Transpose, p1, p2));
+            }
+            _ => edits.push(Edit::new(operation, p1, p2)),
+        }
+        (i1, i2) = (p1, p2);
     }
-    for i in 1..=word2.len() {
-        table[0][i].value = i;
+    edits.reverse();
+    edits
+}
+
+/// Edit distance without transpositions, keeping only two rows of the table.
+/// Only cells that can be reached within max_distance are computed (a band around the diagonal),
+/// and it stops as soon as every cell in a row exceeds max_distance. None if the distance is larger than max_distance
+pub(crate) fn bounded_distance<T: Eq>(word1: &[T], word2: &[T], costs: &EditCosts, max_distance: usize) -> Option<usize> {
+    const UNREACHABLE: usize = usize::MAX;
+    let (n, m) = (word1.len(), word2.len());
+    // the difference in length must at least be inserted or deleted
+    let length_cost = if n > m { (n - m) * costs.deletion } else { (m - n) * costs.insertion };
+    if length_cost > max_distance {
+        return None;
     }
+    // cell (i, j) needs at least j - i insertions, or i - j deletions
+    let right = max_distance.checked_div(costs.insertion).unwrap_or(m);
+    let left = max_distance.checked_div(costs.deletion).unwrap_or(n);
 
-    for (i1, g1) in word1.graphemes(true).enumerate() {
-        for (i2, g2) in word2.graphemes(true).enumerate() {
-            let d_del = table[i1][i2 + 1].value + 1; //deletion
-            let d_ins = table[i1 + 1][i2].value + 1; //insertion
-            let d_sub = table[i1][i2].value + (if g1 == g2 { 0 } else { 2 }); // substitution
-            let min = usize::min(d_del, usize::min(d_ins, d_sub));
-            let element = table[i1 + 1].get_mut(i2 + 1).unwrap();
-            element.value = min;
+    let mut previous: Vec<usize> = (0..=m).map(|j| if j <= right { j * costs.insertion } else { UNREACHABLE }).collect();
+    let mut current = vec![UNREACHABLE; m + 1];
+    for i in 1..=n {
+        let from = i.saturating_sub(left);
+        let to = usize::min(m, i.saturating_add(right));
+        // cells just outside of the band, that the next row reads
+        if from > 0 {
+            current[from - 1] = UNREACHABLE;
+        }
+        if to < m {
+            current[to + 1] = UNREACHABLE;
+        }
+        if from == 0 {
+            current[0] = i * costs.deletion;
         }
+        for j in usize::max(from, 1)..=to {
+            let d_sub = previous[j - 1].saturating_add(if word1[i - 1] == word2[j - 1] { 0 } else { costs.substitution });
+            let d_del = previous[j].saturating_add(costs.deletion);
+            let d_ins = current[j - 1].saturating_add(costs.insertion);
+            current[j] = usize::min(d_sub, usize::min(d_del, d_ins));
+        }
+        // every path to the last cell passes this row, and costs are never negative
+        if current[from..=to].iter().all(|&value| value > max_distance) {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
     }
-    table
+    Some(previous[m]).filter(|&distance| distance <= max_distance)
+}
+
+/// The Jaro similarity of two strings, between 0 (nothing in common) and 1 (equal), on grapheme clusters.
+///
+/// Graphemes match if they are equal and not further apart than half the length of the longest string, minus one.
+/// With m matches, of which t are in a different order (transposed), the similarity is the mean of m / |s1|, m / |s2| and (m - t/2) / m.
+///
+/// ```
+/// use rltk::metrics::distance::jaro_similarity;
+///
+/// assert!((jaro_similarity("MARTHA", "MARHTA") - 0.944444).abs() < 1e-6);
+/// ```
+pub fn jaro_similarity(s1: &str, s2: &str) -> f64 {
+    let (s1, s2) = (Unit::Graphemes.split(s1), Unit::Graphemes.split(s2));
+    sequence_jaro_similarity(&s1, &s2)
 }
 
+fn sequence_jaro_similarity<T: Eq>(s1: &[T], s2: &[T]) -> f64 {
+    if s1.is_empty() && s2.is_empty() {
+        return 1.0;
+    }
+    let window = (usize::max(s1.len(), s2.len()) / 2).saturating_sub(1);
+    let mut matched2 = vec![false; s2.len()];
+    let mut matches1 = Vec::new();
+    for (i, unit) in s1.iter().enumerate() {
+        let from = i.saturating_sub(window);
+        let to = usize::min(s2.len(), i + window + 1);
+        if let Some(j) = (from..to).find(|&j| !matched2[j] && s2[j] == *unit) {
+            matched2[j] = true;
+            matches1.push(unit);
+        }
+    }
+    if matches1.is_empty() {
+        return 0.0;
+    }
+    let matches2 = s2.iter().zip(&matched2).filter(|(_, matched)| **matched).map(|(unit, _)| unit);
+    let half_transpositions = matches1.iter().zip(matches2).filter(|(a, b)| **a != *b).count();
+    let m = matches1.len() as f64;
+    let t = half_transpositions as f64 / 2.0;
+    (m / s1.len() as f64 + m / s2.len() as f64 + (m - t) / m) / 3.0
+}
+
+/// The Jaro-Winkler similarity: the Jaro similarity, raised for strings with a common prefix.
+/// With a common prefix of length l, up to max_prefix_length, it is jaro + l * prefix_weight * (1 - jaro).
+/// The usual values are a prefix weight of 0.1 and a maximum prefix length of 4.
+/// # Panics
+/// if prefix_weight * max_prefix_length is more than 1, as the similarity could then exceed 1
+///
+/// ```
+/// use rltk::metrics::distance::jaro_winkler_similarity;
+///
+/// assert!((jaro_winkler_similarity("MARTHA", "MARHTA", 0.1, 4) - 0.961111).abs() < 1e-6);
+/// ```
+pub fn jaro_winkler_similarity(s1: &str, s2: &str, prefix_weight: f64, max_prefix_length: usize) -> f64 {
+    assert!(prefix_weight >= 0.0 && prefix_weight * max_prefix_length as f64 <= 1.0,
+        "prefix weight times maximum prefix length should be between 0 and 1");
+    let (s1, s2) = (Unit::Graphemes.split(s1), Unit::Graphemes.split(s2));
+    let jaro = sequence_jaro_similarity(&s1, &s2);
+    let prefix = s1.iter().zip(&s2).take(max_prefix_length).take_while(|(a, b)| a == b).count();
+    jaro + prefix as f64 * prefix_weight * (1.0 - jaro)
+}
+
+/// The number of positions at which the grapheme clusters of two strings differ. None if the strings have a different length
+///
+/// ```
+/// use rltk::metrics::distance::hamming_distance;
+///
+/// assert_eq!(hamming_distance("karolin", "kathrin"), Some(3));
+/// assert_eq!(hamming_distance("ñandú", "ñandu"), Some(1));
+/// assert_eq!(hamming_distance("abc", "ab"), None);
+/// ```
+pub fn hamming_distance(s1: &str, s2: &str) -> Option<usize> {
+    let (s1, s2) = (Unit::Graphemes.split(s1), Unit::Graphemes.split(s2));
+    (s1.len() == s2.len()).then(|| s1.iter().zip(&s2).filter(|(a, b)| a != b).count())
+}
+
+/// The length of the longest common subsequence of the grapheme clusters of two strings:
+/// the graphemes that occur in both, in the same order, but not necessarily next to each other
+pub fn lcs_length(s1: &str, s2: &str) -> usize {
+    let (s1, s2) = (Unit::Graphemes.split(s1), Unit::Graphemes.split(s2));
+    let mut previous = vec![0; s2.len() + 1];
+    let mut current = vec![0; s2.len() + 1];
+    for unit in &s1 {
+        for (j, other) in s2.iter().enumerate() {
+            current[j + 1] = if unit == other { previous[j] + 1 } else { usize::max(previous[j + 1], current[j]) };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[s2.len()]
+}
+
+/// Twice the length of the longest common subsequence, divided by the total length of the strings: 1 for equal strings.
+///
+/// ```
+/// use rltk::metrics::distance::lcs_ratio;
+///
+/// // the longest common subsequence is "ace"
+/// assert_eq!(lcs_ratio("abcde", "ace"), 0.75);
+/// ```
+pub fn lcs_ratio(s1: &str, s2: &str) -> f64 {
+    let total = Unit::Graphemes.split(s1).len() + Unit::Graphemes.split(s2).len();
+    if total == 0 {
+        return 1.0;
+    }
+    2.0 * lcs_length(s1, s2) as f64 / total as f64
+}
+
+/// 0 if the labels are equal, 1 otherwise
+pub fn binary_distance<T: PartialEq>(label1: &T, label2: &T) -> f64 {
+    if label1 == label2 { 0.0 } else { 1.0 }
+}
+
+/// The fraction of the union of two sets that is not in their intersection: 0 for equal sets, 1 for disjoint sets.
+/// 0 if both sets are empty
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rltk::metrics::distance::jaccard_distance;
+///
+/// let a = HashSet::from(["the", "black", "cat"]);
+/// let b = HashSet::from(["the", "cat", "sat"]);
+/// assert_eq!(jaccard_distance(&a, &b), 0.5);
+/// ```
+pub fn jaccard_distance<T: Eq + Hash>(label1: &HashSet<T>, label2: &HashSet<T>) -> f64 {
+    let union = label1.union(label2).count();
+    if union == 0 {
+        return 0.0;
+    }
+    (union - label1.intersection(label2).count()) as f64 / union as f64
+}
+
+/// Measuring Agreement on Set-valued Items (Passonneau 2006): the Jaccard distance, made larger when neither set is a subset of the other.
+/// 1 - |intersection| / |union| * m, with m 1 for equal sets, 0.67 if one is a subset of the other, 0.33 if they overlap and 0 otherwise.
+/// 0 if both sets are empty
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rltk::metrics::distance::masi_distance;
+///
+/// let a = HashSet::from(["x", "y"]);
+/// let b = HashSet::from(["x", "y", "z"]);
+/// assert!((masi_distance(&a, &b) - (1.0 - 2.0 / 3.0 * 0.67)).abs() < 1e-12);
+/// ```
+pub fn masi_distance<T: Eq + Hash>(label1: &HashSet<T>, label2: &HashSet<T>) -> f64 {
+    let union = label1.union(label2).count();
+    if union == 0 {
+        return 0.0;
+    }
+    let intersection = label1.intersection(label2).count();
+    let monotonicity = if label1.len() == label2.len() && label1.len() == intersection {
+        1.0
+    } else if intersection == usize::min(label1.len(), label2.len()) {
+        0.67
+    } else if intersection > 0 {
+        0.33
+    } else {
+        0.0
+    };
+    1.0 - intersection as f64 / union as f64 * monotonicity
+}
+
+/// The squared difference of two labels on an interval scale, like ratings
+pub fn interval_distance(label1: f64, label2: f64) -> f64 {
+    (label1 - label2).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::assert_close;
+    use crate::util::ngrams;
+
+    #[test]
+    fn test_jaro() {
+        assert_close(jaro_similarity("DWAYNE", "DUANE"), 0.822222, 1e-6);
+        assert_close(jaro_similarity("DIXON", "DICKSONX"), 0.766667, 1e-6);
+        assert_close(jaro_similarity("abc", "xyz"), 0.0, 1e-6);
+        assert_close(jaro_similarity("", ""), 1.0, 1e-6);
+        assert_close(jaro_similarity("", "a"), 0.0, 1e-6);
+        assert_close(jaro_similarity("a", "a"), 1.0, 1e-6);
+    }
+
+    #[test]
+    fn test_jaro_winkler() {
+        assert_close(jaro_winkler_similarity("DWAYNE", "DUANE", 0.1, 4), 0.84, 1e-6);
+        assert_close(jaro_winkler_similarity("DIXON", "DICKSONX", 0.1, 4), 0.813333, 1e-6);
+        // no prefix bonus without a prefix
+        assert_close(jaro_winkler_similarity("DIXON", "DICKSONX", 0.1, 0), jaro_similarity("DIXON", "DICKSONX"), 1e-6);
+        // the prefix is at most max_prefix_length long
+        let jaro = jaro_similarity("abcdefgh", "abcdefgx");
+        assert_close(jaro_winkler_similarity("abcdefgh", "abcdefgx", 0.1, 4), jaro + 0.4 * (1.0 - jaro), 1e-6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_jaro_winkler_weight_too_large() {
+        jaro_winkler_similarity("a", "a", 0.3, 4);
+    }
+
+    #[test]
+    fn test_graphemes() {
+        // an emoji with a skin tone modifier is one grapheme, but two chars
+        assert_eq!(hamming_distance("👍🏽a", "👍🏿a"), Some(1));
+        assert_eq!(hamming_distance("👍🏽a", "👍a"), Some(1));
+        assert_close(jaro_similarity("José", "Jose"), (0.75 + 0.75 + 1.0) / 3.0, 1e-6);
+        // c, r, m, e, space, b, r, l and the final e
+        assert_eq!(lcs_length("crème brûlée", "creme brulee"), 9);
+        assert_close(lcs_ratio("", ""), 1.0, 1e-6);
+        assert_close(lcs_ratio("über", "über"), 1.0, 1e-6);
+    }
+
+    #[test]
+    fn test_set_distances() {
+        let empty: HashSet<&str> = HashSet::new();
+        let ab = HashSet::from(["a", "b"]);
+        let bc = HashSet::from(["b", "c"]);
+        let cd = HashSet::from(["c", "d"]);
+        assert_close(jaccard_distance(&ab, &ab), 0.0, 1e-6);
+        assert_close(jaccard_distance(&ab, &bc), 2.0 / 3.0, 1e-6);
+        assert_close(jaccard_distance(&ab, &cd), 1.0, 1e-6);
+        assert_close(jaccard_distance(&empty, &empty), 0.0, 1e-6);
+        assert_close(masi_distance(&ab, &ab), 0.0, 1e-6);
+        assert_close(masi_distance(&ab, &bc), 1.0 - 0.33 / 3.0, 1e-6);
+        assert_close(masi_distance(&ab, &cd), 1.0, 1e-6);
+        assert_close(masi_distance(&empty, &ab), 1.0, 1e-6);
+        assert_close(binary_distance(&ab, &ab), 0.0, 1e-6);
+        assert_close(binary_distance(&"a", &"b"), 1.0, 1e-6);
+        assert_close(interval_distance(1.0, 4.0), 9.0, 1e-6);
+    }
+
+    #[test]
+    fn test_ngram_sets() {
+        let bigram_set = |tokens: &'static [&'static str]| -> HashSet<Vec<&'static str>> {
+            ngrams(tokens.iter(), 2).map(|ngram| ngram.copied().collect()).collect()
+        };
+        let a = bigram_set(&["the", "cat", "sat", "on", "the", "mat"]);
+        let b = bigram_set(&["the", "cat", "sat", "on", "a", "mat"]);
+        // the cat, cat sat and sat on are shared, of 7 bigrams
+        assert_close(jaccard_distance(&a, &b), 4.0 / 7.0, 1e-6);
+    }
+}
@@ -1,23 +1,289 @@
+pub mod agreement;
+pub mod association;
+pub mod bleu;
+pub mod confusion;
 pub mod distance;
+pub mod scores;
+
+pub use scores::{accuracy, f_measure, precision, recall};
+
+use std::hash::Hash;
+
+use distance::{Edit, EditCosts, Unit};
 
 /// Calculate the Levenshtein edit-distance between two strings.
-/// The edit distance is the number of characters that need to be substituted, inserted, or deleted, to transform s1 into s2.
+/// The edit distance is the number of characters (grapheme clusters) that need to be substituted, inserted, or deleted, to transform s1 into s2.
 /// For example, transforming “rain” to “shine” requires three steps, consisting of two substitutions and one insertion:
 /// “rain” -> “sain” -> “shin” -> “shine”.
 /// These operations could have been done in other orders, but at least three steps are needed.
 ///
-/// substitution cost is (for now at least) hardcoded as 2
-pub fn edit_distance(s1: &str, s2: &str) -> usize {
-    distance::get_edit_distance_table(s1, s2)[s1.len()][s2.len()].value
+/// substitution_cost: the cost of a substitution, insertions and deletions cost 1. With cost 2 a substitution is never
+/// cheaper than a deletion plus an insertion.
+/// transpositions: if true, swapping two adjacent characters costs 1 (the Damerau-Levenshtein distance)
+///
+/// ```
+/// use rltk::metrics::edit_distance;
+///
+/// assert_eq!(edit_distance("rain", "shine", 1, false), 3);
+/// assert_eq!(edit_distance("intention", "execution", 2, false), 8);
+/// assert_eq!(edit_distance("abc", "acb", 1, true), 1);
+/// ```
+pub fn edit_distance(s1: &str, s2: &str, substitution_cost: usize, transpositions: bool) -> usize {
+    let costs = EditCosts {
+        substitution: substitution_cost,
+        transposition: if transpositions { Some(1) } else { None },
+        ..EditCosts::levenshtein()
+    };
+    edit_distance_with_costs(s1, s2, &costs, Unit::Graphemes)
+}
+
+/// The edit distance between two strings, with a cost for every type of operation, counting in the given unit
+///
+/// ```
+/// use rltk::metrics::edit_distance_with_costs;
+/// use rltk::metrics::distance::{EditCosts, Unit};
+///
+/// let costs = EditCosts { insertion: 1, deletion: 3, substitution: 5, transposition: None };
+/// assert_eq!(edit_distance_with_costs("ab", "a", &costs, Unit::Graphemes), 3);
+/// assert_eq!(edit_distance_with_costs("a", "ab", &costs, Unit::Graphemes), 1);
+/// // a deletion plus an insertion is cheaper than a substitution
+/// assert_eq!(edit_distance_with_costs("a", "b", &costs, Unit::Graphemes), 4);
+///
+/// let costs = EditCosts::levenshtein();
+/// assert_eq!(edit_distance_with_costs("the black cat", "the cat", &costs, Unit::Tokens), 1);
+/// // é takes 2 bytes
+/// assert_eq!(edit_distance_with_costs("café", "cafe", &costs, Unit::Bytes), 2);
+/// ```
+pub fn edit_distance_with_costs(s1: &str, s2: &str, costs: &EditCosts, unit: Unit) -> usize {
+    sequence_edit_distance(&unit.split(s1), &unit.split(s2), costs)
+}
+
+/// The operations of an optimal alignment of two strings with Levenshtein costs, on grapheme clusters:
+/// how to turn s1 into s2 with the fewest insertions, deletions and substitutions.
+/// Every unit of s1 and s2 takes part in exactly one operation.
+///
+/// ```
+/// use rltk::metrics::edit_distance_align;
+/// use rltk::metrics::distance::{Edit, EditOperation::*};
+///
+/// let alignment = edit_distance_align("rain", "shine");
+/// let operations: Vec<_> = alignment.iter().map(|edit| edit.operation).collect();
+/// assert_eq!(operations, vec![Substitute, Substitute, Match, Match, Insert]);
+/// assert_eq!(alignment[4], Edit { operation: Insert, source: 4, target: 4 });
+/// ```
+pub fn edit_distance_align(s1: &str, s2: &str) -> Vec<Edit> {
+    edit_distance_align_with_costs(s1, s2, &EditCosts::levenshtein(), Unit::Graphemes)
+}
+
+/// The operations of an optimal alignment of two strings, with a cost for every type of operation, in the given unit
+pub fn edit_distance_align_with_costs(s1: &str, s2: &str, costs: &EditCosts, unit: Unit) -> Vec<Edit> {
+    distance::backtrace(&distance::get_edit_distance_table(&unit.split(s1), &unit.split(s2), costs))
+}
+
+/// The edit distance between two sequences of any type, like tokens or tags
+///
+/// ```
+/// use rltk::metrics::sequence_edit_distance;
+/// use rltk::metrics::distance::EditCosts;
+///
+/// assert_eq!(sequence_edit_distance(&["DT", "JJ", "NN"], &["DT", "NN"], &EditCosts::levenshtein()), 1);
+/// ```
+pub fn sequence_edit_distance<T: Eq + Hash>(s1: &[T], s2: &[T], costs: &EditCosts) -> usize {
+    if costs.transposition.is_none() {
+        // only two rows of the table are needed
+        return distance::bounded_distance(s1, s2, costs, usize::MAX).unwrap_or(usize::MAX);
+    }
+    let table = distance::get_edit_distance_table(s1, s2, costs);
+    table.last().and_then(|row| row.last()).map(|element| element.value).unwrap_or(0)
+}
+
+/// The edit distance between two strings if it is at most max_distance, otherwise None.
+/// Much faster than [edit_distance_with_costs] for a small max_distance, as only the part of the table
+/// that can be reached within max_distance is computed, and it stops as soon as the distance is known to be larger.
+/// With transpositions the full distance is computed.
+///
+/// ```
+/// use rltk::metrics::bounded_edit_distance;
+/// use rltk::metrics::distance::{EditCosts, Unit};
+///
+/// let costs = EditCosts::levenshtein();
+/// assert_eq!(bounded_edit_distance("kitten", "sitting", &costs, Unit::Graphemes, 3), Some(3));
+/// assert_eq!(bounded_edit_distance("kitten", "sitting", &costs, Unit::Graphemes, 2), None);
+/// ```
+pub fn bounded_edit_distance(s1: &str, s2: &str, costs: &EditCosts, unit: Unit, max_distance: usize) -> Option<usize> {
+    bounded_sequence_edit_distance(&unit.split(s1), &unit.split(s2), costs, max_distance)
+}
+
+/// The edit distance between two sequences if it is at most max_distance, otherwise None. See [bounded_edit_distance]
+pub fn bounded_sequence_edit_distance<T: Eq + Hash>(s1: &[T], s2: &[T], costs: &EditCosts, max_distance: usize) -> Option<usize> {
+    if costs.transposition.is_none() {
+        distance::bounded_distance(s1, s2, costs, max_distance)
+    } else {
+        Some(sequence_edit_distance(s1, s2, costs)).filter(|&distance| distance <= max_distance)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use unicode_segmentation::UnicodeSegmentation;
+
     use super::*;
+    use crate::util::random::XorShift;
+    use distance::EditOperation;
 
     #[test]
     fn test_get_levenshtein_distance() {
-        assert_eq!(edit_distance("intention", "execution"), 8);
+        assert_eq!(edit_distance("intention", "execution", 2, false), 8);
+        assert_eq!(edit_distance("intention", "execution", 1, false), 5);
+        assert_eq!(edit_distance("", "abc", 1, false), 3);
+        assert_eq!(edit_distance("abc", "", 1, false), 3);
+        assert_eq!(edit_distance("", "", 1, false), 0);
+    }
+
+    #[test]
+    fn test_transpositions() {
+        // delete b, then swap: unrestricted Damerau-Levenshtein allows edits between the transposed characters
+        assert_eq!(edit_distance("abc", "ca", 1, false), 3);
+        assert_eq!(edit_distance("abc", "ca", 1, true), 2);
+        // no adjacent swap helps here
+        assert_eq!(edit_distance("wants", "wasp", 1, false), 3);
+        assert_eq!(edit_distance("wants", "wasp", 1, true), 3);
+        assert_eq!(edit_distance("ab", "ba", 2, false), 2);
+        assert_eq!(edit_distance("ab", "ba", 2, true), 1);
+        assert_eq!(edit_distance("acb", "abc", 1, true), 1);
+        assert_eq!(edit_distance("ab", "bca", 1, true), 2);
+    }
+
+    #[test]
+    fn test_costs() {
+        let costs = EditCosts { insertion: 2, deletion: 2, substitution: 3, transposition: Some(1) };
+        assert_eq!(edit_distance_with_costs("ab", "ba", &costs, Unit::Graphemes), 1);
+        assert_eq!(edit_distance_with_costs("ab", "ac", &costs, Unit::Graphemes), 3);
+        assert_eq!(edit_distance_with_costs("ab", "abcd", &costs, Unit::Graphemes), 4);
+    }
+
+    #[test]
+    fn test_accents() {
+        // precomposed é against e followed by a combining acute accent
+        let precomposed = "caf\u{e9}";
+        let combining = "cafe\u{301}";
+        let costs = EditCosts::levenshtein();
+        assert_eq!(edit_distance("café", "cafe", 1, false), 1);
+        assert_eq!(edit_distance("naïve", "naive", 1, false), 1);
+        assert_eq!(edit_distance("über", "uber", 1, false), 1);
+        assert_eq!(edit_distance_with_costs(precomposed, combining, &costs, Unit::Graphemes), 1);
+        assert_eq!(edit_distance_with_costs(precomposed, combining, &costs, Unit::Chars), 2);
+        assert_eq!(edit_distance_with_costs(precomposed, combining, &costs, Unit::Bytes), 3);
+        assert_eq!(edit_distance_with_costs("crème brûlée", "creme brulee", &costs, Unit::Tokens), 2);
+    }
+
+    #[test]
+    fn test_emoji() {
+        let costs = EditCosts::levenshtein();
+        // thumbs up, and with a skin tone modifier
+        assert_eq!(edit_distance_with_costs("👍", "👍🏽", &costs, Unit::Graphemes), 1);
+        assert_eq!(edit_distance_with_costs("👍", "👍🏽", &costs, Unit::Chars), 1);
+        assert_eq!(edit_distance_with_costs("👍", "👍🏽", &costs, Unit::Bytes), 4);
+        // a family: three emoji joined by zero width joiners, a single grapheme
+        assert_eq!(edit_distance("ok 👨‍👩‍👧", "ok", 1, false), 2);
+        assert_eq!(edit_distance_with_costs("ok 👨‍👩‍👧", "ok", &costs, Unit::Chars), 6);
+        assert_eq!(edit_distance("a👍b", "b👍a", 1, false), 2);
+        assert_eq!(edit_distance("👍🏽🎉", "🎉👍🏽", 1, true), 1);
+    }
+
+    fn alignment_cost(alignment: &[Edit], costs: &EditCosts) -> usize {
+        alignment.iter()
+            .map(|edit| match edit.operation {
+                EditOperation::Match => 0,
+                EditOperation::Substitute => costs.substitution,
+                EditOperation::Insert => costs.insertion,
+                EditOperation::Delete => costs.deletion,
+                EditOperation::Transpose => costs.transposition.unwrap(),
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_align() {
+        use EditOperation::*;
+        let alignment = edit_distance_align("kitten", "sitting");
+        assert_eq!(alignment, vec![
+            Edit { operation: Substitute, source: 0, target: 0 },
+            Edit { operation: Match, source: 1, target: 1 },
+            Edit { operation: Match, source: 2, target: 2 },
+            Edit { operation: Match, source: 3, target: 3 },
+            Edit { operation: Substitute, source: 4, target: 4 },
+            Edit { operation: Match, source: 5, target: 5 },
+            Edit { operation: Insert, source: 6, target: 6 },
+        ]);
+        let alignment = edit_distance_align("abc", "");
+        assert_eq!(alignment.iter().map(|edit| (edit.operation, edit.source)).collect::<Vec<_>>(), vec![(Delete, 0), (Delete, 1), (Delete, 2)]);
+        assert!(edit_distance_align("", "").is_empty());
+    }
+
+    #[test]
+    fn test_align_transpositions() {
+        use EditOperation::*;
+        let costs = EditCosts::damerau_levenshtein();
+        let alignment = edit_distance_align_with_costs("abc", "acb", &costs, Unit::Graphemes);
+        assert_eq!(alignment, vec![Edit { operation: Match, source: 0, target: 0 }, Edit { operation: Transpose, source: 1, target: 1 }]);
+
+        // delete b between the transposed pair a and c
+        let alignment = edit_distance_align_with_costs("abc", "ca", &costs, Unit::Graphemes);
+        assert_eq!(alignment, vec![Edit { operation: Transpose, source: 0, target: 0 }, Edit { operation: Delete, source: 1, target: 1 }]);
+    }
+
+    #[test]
+    fn test_alignment_cost_is_distance() {
+        let pairs = [("intention", "execution"), ("crème brûlée", "creme brulee"), ("wants", "wasp"), ("abcdef", "badcfe"), ("ab", "bxya")];
+        for costs in [EditCosts::levenshtein(), EditCosts::damerau_levenshtein(), EditCosts { insertion: 2, deletion: 3, substitution: 4, transposition: Some(2) }] {
+            for (s1, s2) in pairs {
+                let alignment = edit_distance_align_with_costs(s1, s2, &costs, Unit::Graphemes);
+                assert_eq!(alignment_cost(&alignment, &costs), edit_distance_with_costs(s1, s2, &costs, Unit::Graphemes), "{} {}", s1, s2);
+                // every unit is used once
+                let sources = alignment.iter().filter(|edit| edit.operation != EditOperation::Insert).count()
+                    + alignment.iter().filter(|edit| edit.operation == EditOperation::Transpose).count();
+                assert_eq!(sources, s1.graphemes(true).count());
+            }
+        }
+    }
+
+    #[test]
+    fn test_bounded() {
+        let costs = EditCosts::levenshtein();
+        assert_eq!(bounded_edit_distance("", "", &costs, Unit::Graphemes, 0), Some(0));
+        assert_eq!(bounded_edit_distance("abc", "abc", &costs, Unit::Graphemes, 0), Some(0));
+        assert_eq!(bounded_edit_distance("abc", "abd", &costs, Unit::Graphemes, 0), None);
+        // the difference in length alone is too much
+        assert_eq!(bounded_edit_distance("a", "abcdef", &costs, Unit::Graphemes, 4), None);
+        assert_eq!(bounded_edit_distance("café", "cafe", &costs, Unit::Bytes, 2), Some(2));
+        assert_eq!(bounded_edit_distance("café", "cafe", &costs, Unit::Bytes, 1), None);
+        let damerau = EditCosts::damerau_levenshtein();
+        assert_eq!(bounded_edit_distance("abc", "acb", &damerau, Unit::Graphemes, 1), Some(1));
+        assert_eq!(bounded_edit_distance("abc", "cba", &damerau, Unit::Graphemes, 1), None);
+    }
+
+    #[test]
+    fn test_two_rows_and_bounded_agree_with_table() {
+        let mut random = XorShift::new(3);
+        let word = |random: &mut XorShift| -> Vec<u8> {
+            let length = random.below(12);
+            (0..length).map(|_| b"abc"[random.below(3)]).collect()
+        };
+        let cost_sets = [EditCosts::levenshtein(), EditCosts { insertion: 2, deletion: 1, substitution: 2, transposition: None },
+            EditCosts { insertion: 1, deletion: 0, substitution: 3, transposition: None }];
+        for _ in 0..300 {
+            let (s1, s2) = (word(&mut random), word(&mut random));
+            for costs in &cost_sets {
+                let table = distance::get_edit_distance_table(&s1, &s2, costs);
+                let expected = table[s1.len()][s2.len()].value;
+                assert_eq!(sequence_edit_distance(&s1, &s2, costs), expected);
+                for max_distance in 0..8 {
+                    let bounded = bounded_sequence_edit_distance(&s1, &s2, costs, max_distance);
+                    assert_eq!(bounded, Some(expected).filter(|&d| d <= max_distance), "{:?} {:?} {:?} {}", s1, s2, costs, max_distance);
+                }
+            }
+        }
     }
 
     #[test]
@@ -29,7 +295,10 @@ mod tests {
         let outcome: [[usize; 10]; 10] = [[0, 1, 2, 3, 4, 5, 6, 7, 8, 9], [1, 2, 3, 4, 5, 6, 7, 6, 7, 8], [2, 3, 4, 5, 6, 7, 8, 7, 8, 7], [3, 4, 5, 6, 7, 8, 7, 8, 9, 8], [4, 3, 4, 5, 6, 7, 8, 9, 10, 9],
             [5, 4, 5, 6, 7, 8, 9, 10, 11, 10], [6, 5, 6, 7, 8, 9, 8, 9, 10, 11], [7, 6, 7, 8, 9, 10, 9, 8, 9, 10], [8, 7, 8, 9, 10, 11, 10, 9, 8, 9], [9, 8, 9, 10, 11, 12, 11, 10, 9, 8]];
 
-        let tab = distance::get_edit_distance_table(word1, word2);
+        let costs = EditCosts { substitution: 2, ..EditCosts::levenshtein() };
+        let word1: Vec<&str> = word1.graphemes(true).collect();
+        let word2: Vec<&str> = word2.graphemes(true).collect();
+        let tab = distance::get_edit_distance_table(&word1, &word2, &costs);
 
         for (rowindex, row) in tab.iter().enumerate() {
             for (colindex, element) in row.iter().enumerate() {
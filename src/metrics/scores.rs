@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// The fraction of the items in test that are equal to the item at the same position in reference
+/// # Panics
+/// if reference and test have different lengths
+///
+/// ```
+/// use rltk::metrics::accuracy;
+///
+/// assert_eq!(accuracy(&["DT", "NN", "VB", "NN"], &["DT", "NN", "NN", "NN"]), 0.75);
+/// ```
+pub fn accuracy<T: PartialEq>(reference: &[T], test: &[T]) -> f64 {
+    assert_eq!(reference.len(), test.len(), "reference and test should have the same length");
+    if reference.is_empty() {
+        return 0.0;
+    }
+    let correct = reference.iter().zip(test).filter(|(r, t)| r == t).count();
+    correct as f64 / reference.len() as f64
+}
+
+/// The fraction of the test items that are in reference. None if test is empty
+///
+/// ```
+/// use std::collections::HashSet;
+/// use rltk::metrics::{f_measure, precision, recall};
+///
+/// let reference: HashSet<&str> = HashSet::from(["a", "b", "c", "d"]);
+/// let test: HashSet<&str> = HashSet::from(["a", "b", "x"]);
+/// assert_eq!(precision(&reference, &test), Some(2.0 / 3.0));
+/// assert_eq!(recall(&reference, &test), Some(0.5));
+/// assert_eq!(f_measure(&reference, &test, 0.5), Some(4.0 / 7.0));
+/// ```
+pub fn precision<T: Eq + Hash>(reference: &HashSet<T>, test: &HashSet<T>) -> Option<f64> {
+    ratio(reference.intersection(test).count(), test.len())
+}
+
+/// The fraction of the reference items that are in test. None if reference is empty
+pub fn recall<T: Eq + Hash>(reference: &HashSet<T>, test: &HashSet<T>) -> Option<f64> {
+    ratio(reference.intersection(test).count(), reference.len())
+}
+
+/// The weighted harmonic mean of precision and recall: 1 / (alpha / precision + (1 - alpha) / recall).
+/// With alpha 0.5 this is the F1 score. None if precision or recall is undefined, 0 if either is 0
+pub fn f_measure<T: Eq + Hash>(reference: &HashSet<T>, test: &HashSet<T>, alpha: f64) -> Option<f64> {
+    Some(weighted_harmonic_mean(precision(reference, test)?, recall(reference, test)?, alpha))
+}
+
+/// numerator / denominator, None if the denominator is 0
+pub(crate) fn ratio(numerator: usize, denominator: usize) -> Option<f64> {
+    (denominator > 0).then(|| numerator as f64 / denominator as f64)
+}
+
+pub(crate) fn weighted_harmonic_mean(precision: f64, recall: f64, alpha: f64) -> f64 {
+    if precision == 0.0 || recall == 0.0 {
+        0.0
+    } else {
+        1.0 / (alpha / precision + (1.0 - alpha) / recall)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_sets() {
+        let empty: HashSet<usize> = HashSet::new();
+        let some: HashSet<usize> = HashSet::from([1, 2]);
+        assert_eq!(precision(&some, &empty), None);
+        assert_eq!(recall(&empty, &some), None);
+        assert_eq!(f_measure(&some, &empty, 0.5), None);
+        assert_eq!(f_measure(&some, &HashSet::from([3]), 0.5), Some(0.0));
+        assert_eq!(accuracy::<usize>(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn test_alpha() {
+        let reference = HashSet::from([1, 2, 3, 4]);
+        let test = HashSet::from([1, 2]);
+        // precision 1, recall 0.5
+        assert_eq!(f_measure(&reference, &test, 1.0), Some(1.0));
+        assert_eq!(f_measure(&reference, &test, 0.0), Some(0.5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_accuracy_lengths() {
+        accuracy(&[1, 2], &[1]);
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::parse::Parser;
+use crate::parse::grammar::{Grammar, Symbol};
+use crate::tree::{Child, Tree};
+
+/// Earley chart parser for context free grammars. Handles any grammar, including left recursion and empty productions.
+///
+/// ```
+/// use rltk::parse::Parser;
+/// use rltk::parse::earley::EarleyParser;
+/// use rltk::parse::grammar::Grammar;
+///
+/// let grammar = Grammar::from_text("
+///     S -> NP VP
+///     PP -> P NP
+///     NP -> Det N | Det N PP | 'I'
+///     VP -> V NP | VP PP
+///     Det -> 'an' | 'my'
+///     N -> 'elephant' | 'pajamas'
+///     V -> 'shot'
+///     P -> 'in'
+/// ").unwrap();
+/// let parser = EarleyParser::new(&grammar);
+/// let trees = parser.parse(&["I", "shot", "an", "elephant", "in", "my", "pajamas"]);
+/// assert_eq!(trees.len(), 2);
+/// ```
+pub struct EarleyParser<'g> {
+    grammar: &'g Grammar,
+    nullable: HashSet<&'g str>,
+}
+
+/// A production with a dot in its right hand side, for the part of the input from `start` on
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Item {
+    production: usize,
+    dot: usize,
+    start: usize,
+}
+
+impl<'g> EarleyParser<'g> {
+    pub fn new(grammar: &'g Grammar) -> Self {
+        Self { grammar, nullable: grammar.nullable() }
+    }
+
+    /// Fills the chart: for every position in the tokens, the items that are consistent with the tokens up to that position
+    fn chart(&self, tokens: &[&str]) -> Vec<Vec<Item>> {
+        let productions = self.grammar.productions();
+        let mut chart: Vec<Vec<Item>> = vec![Vec::new(); tokens.len() + 1];
+        let mut seen: Vec<HashSet<Item>> = vec![HashSet::new(); tokens.len() + 1];
+
+        fn add(chart: &mut [Vec<Item>], seen: &mut [HashSet<Item>], position: usize, item: Item) {
+            if seen[position].insert(item) {
+                chart[position].push(item);
+            }
+        }
+
+        for (index, production) in productions.iter().enumerate() {
+            if production.lhs == self.grammar.start() {
+                add(&mut chart, &mut seen, 0, Item { production: index, dot: 0, start: 0 });
+            }
+        }
+
+        for position in 0..=tokens.len() {
+            // the chart at this position grows while it is processed
+            let mut next = 0;
+            while next < chart[position].len() {
+                let item = chart[position][next];
+                next += 1;
+                let production = &productions[item.production];
+                match production.rhs.get(item.dot) {
+                    Some(Symbol::Nonterminal(name)) => {
+                        // predict
+                        for (index, candidate) in productions.iter().enumerate() {
+                            if &candidate.lhs == name {
+                                add(&mut chart, &mut seen, position, Item { production: index, dot: 0, start: position });
+                            }
+                        }
+                        if self.nullable.contains(name.as_str()) {
+                            add(&mut chart, &mut seen, position, Item { dot: item.dot + 1, ..item });
+                        }
+                    }
+                    Some(Symbol::Terminal(token)) => {
+                        // scan
+                        if tokens.get(position) == Some(&token.as_str()) {
+                            add(&mut chart, &mut seen, position + 1, Item { dot: item.dot + 1, ..item });
+                        }
+                    }
+                    None => {
+                        // complete
+                        let waiting: Vec<Item> = chart[item.start].iter()
+                            .filter(|waiting| matches!(productions[waiting.production].rhs.get(waiting.dot),
+                                Some(Symbol::Nonterminal(name)) if name == &production.lhs))
+                            .copied()
+                            .collect();
+                        for waiting in waiting {
+                            add(&mut chart, &mut seen, position, Item { dot: waiting.dot + 1, ..waiting });
+                        }
+                    }
+                }
+            }
+        }
+        chart
+    }
+}
+
+impl Parser for EarleyParser<'_> {
+    fn parse(&self, tokens: &[&str]) -> Vec<Tree<String>> {
+        let chart = self.chart(tokens);
+        let productions = self.grammar.productions();
+
+        // the complete items: productions by their left hand side and the part of the tokens they cover
+        let mut complete: HashMap<(&str, usize, usize), Vec<usize>> = HashMap::new();
+        for (end, items) in chart.iter().enumerate() {
+            for item in items {
+                let production = &productions[item.production];
+                if item.dot == production.rhs.len() {
+                    complete.entry((production.lhs.as_str(), item.start, end)).or_default().push(item.production);
+                }
+            }
+        }
+
+        let builder = TreeBuilder { grammar: self.grammar, tokens, complete };
+        builder.trees(self.grammar.start(), 0, tokens.len(), &mut Vec::new())
+    }
+}
+
+/// Reads all trees from the complete items in the chart
+struct TreeBuilder<'a> {
+    grammar: &'a Grammar,
+    tokens: &'a [&'a str],
+    complete: HashMap<(&'a str, usize, usize), Vec<usize>>,
+}
+
+impl<'a> TreeBuilder<'a> {
+    /// the trees for a nonterminal covering tokens start..end.
+    /// Trees that contain themselves (through cycles of unary or empty productions) are left out, as there would be infinitely many.
+    fn trees(&self, lhs: &'a str, start: usize, end: usize, active: &mut Vec<(&'a str, usize, usize)>) -> Vec<Tree<String>> {
+        let key = (lhs, start, end);
+        let productions = match self.complete.get(&key) {
+            Some(productions) if !active.contains(&key) => productions,
+            _ => return Vec::new(),
+        };
+        active.push(key);
+        let mut trees = Vec::new();
+        for &production in productions {
+            for children in self.children(&self.grammar.productions()[production].rhs, start, end, active) {
+                trees.push(Tree::new(lhs, children));
+            }
+        }
+        active.pop();
+        trees
+    }
+
+    /// all ways in which the symbols cover tokens start..end
+    fn children(&self, symbols: &'a [Symbol], start: usize, end: usize, active: &mut Vec<(&'a str, usize, usize)>) -> Vec<Vec<Child<String>>> {
+        let (first, rest) = match symbols.split_first() {
+            Some(split) => split,
+            None if start == end => return vec![Vec::new()],
+            None => return Vec::new(),
+        };
+        let mut result = Vec::new();
+        match first {
+            Symbol::Terminal(token) => {
+                if start < end && self.tokens[start] == token {
+                    for mut children in self.children(rest, start + 1, end, active) {
+                        children.insert(0, Child::Leaf(token.clone()));
+                        result.push(children);
+                    }
+                }
+            }
+            Symbol::Nonterminal(name) => {
+                for split in start..=end {
+                    if !self.complete.contains_key(&(name.as_str(), start, split)) {
+                        continue;
+                    }
+                    let rest_children = self.children(rest, split, end, active);
+                    if rest_children.is_empty() {
+                        continue;
+                    }
+                    for tree in self.trees(name, start, split, active) {
+                        for children in &rest_children {
+                            let mut children = children.clone();
+                            children.insert(0, Child::Tree(tree.clone()));
+                            result.push(children);
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(grammar: &str, tokens: &[&str]) -> Vec<String> {
+        let grammar = Grammar::from_text(grammar).unwrap();
+        let mut trees: Vec<String> = EarleyParser::new(&grammar).parse(tokens).iter().map(|tree| tree.to_string()).collect();
+        trees.sort();
+        trees
+    }
+
+    const GROUCHO: &str = "
+        S -> NP VP
+        PP -> P NP
+        NP -> Det N | Det N PP | 'I'
+        VP -> V NP | VP PP
+        Det -> 'an' | 'my'
+        N -> 'elephant' | 'pajamas'
+        V -> 'shot'
+        P -> 'in'
+    ";
+
+    #[test]
+    fn test_ambiguous() {
+        let trees = parse(GROUCHO, &["I", "shot", "an", "elephant", "in", "my", "pajamas"]);
+        assert_eq!(trees, vec![
+            "(S (NP I) (VP (V shot) (NP (Det an) (N elephant) (PP (P in) (NP (Det my) (N pajamas))))))",
+            "(S (NP I) (VP (VP (V shot) (NP (Det an) (N elephant))) (PP (P in) (NP (Det my) (N pajamas)))))",
+        ]);
+    }
+
+    #[test]
+    fn test_no_parse() {
+        assert!(parse(GROUCHO, &["I", "shot"]).is_empty());
+        assert!(parse(GROUCHO, &["I", "shot", "an", "unknown"]).is_empty());
+        assert!(parse(GROUCHO, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_left_recursion() {
+        let trees = parse("E -> E '+' E | 'n'", &["n", "+", "n", "+", "n"]);
+        assert_eq!(trees, vec!["(E (E (E n) + (E n)) + (E n))", "(E (E n) + (E (E n) + (E n)))"]);
+    }
+
+    #[test]
+    fn test_empty_productions() {
+        let trees = parse("S -> A 'b' A\nA -> | 'a'", &["b", "a"]);
+        assert_eq!(trees, vec!["(S (A) b (A a))"]);
+        let trees = parse("S -> A A\nA -> | 'a'", &[]);
+        assert_eq!(trees, vec!["(S (A) (A))"]);
+    }
+
+    #[test]
+    fn test_unary_cycle() {
+        // A -> B -> A could go on forever, a tree never contains a subtree for the same symbol and tokens
+        let trees = parse("S -> A\nA -> B | 'a'\nB -> A", &["a"]);
+        assert_eq!(trees, vec!["(S (A a))"]);
+    }
+}
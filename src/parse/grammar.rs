@@ -0,0 +1,230 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+
+use anyhow::anyhow;
+
+/// A symbol on the right hand side of a production
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    Nonterminal(String),
+    /// a token
+    Terminal(String),
+}
+
+/// A grammar rule: the left hand side nonterminal can be rewritten to the symbols on the right hand side
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Production {
+    pub lhs: String,
+    pub rhs: Vec<Symbol>,
+}
+
+impl Production {
+    pub fn new(lhs: &str, rhs: Vec<Symbol>) -> Self {
+        Self { lhs: lhs.to_string(), rhs }
+    }
+}
+
+/// written like `NP -> Det 'dog'`
+impl fmt::Display for Production {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ->", self.lhs)?;
+        for symbol in &self.rhs {
+            match symbol {
+                Symbol::Nonterminal(name) => write!(f, " {}", name)?,
+                Symbol::Terminal(token) => write!(f, " '{}'", token)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A context free grammar
+///
+/// ```
+/// use rltk::parse::grammar::Grammar;
+///
+/// let grammar = Grammar::from_text("
+///     S -> NP VP
+///     NP -> 'John' | 'Mary'
+///     VP -> 'runs' | V NP
+///     V -> 'sees'
+/// ").unwrap();
+/// assert_eq!(grammar.start(), "S");
+/// assert_eq!(grammar.productions().len(), 6);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Grammar {
+    start: String,
+    productions: Vec<Production>,
+}
+
+impl Grammar {
+    pub fn new(start: &str, productions: Vec<Production>) -> Self {
+        Self { start: start.to_string(), productions }
+    }
+
+    /// Reads a grammar from a file, in the format of [Grammar::from_text]
+    pub fn from_file(filename: &str) -> anyhow::Result<Self> {
+        Self::from_text(&fs::read_to_string(filename)?)
+    }
+
+    /// Reads a grammar with a production per line, like `NP -> Det N | 'I'`.
+    /// Terminals are quoted with single or double quotes, alternatives are separated by `|`,
+    /// and an empty alternative is an empty production. Text after `#` is a comment.
+    /// The left hand side of the first production is the start symbol.
+    pub fn from_text(text: &str) -> anyhow::Result<Self> {
+        let mut productions = Vec::new();
+        for (lhs, alternatives) in parse_lines(text)? {
+            for alternative in alternatives {
+                if let Some(probability) = alternative.probability {
+                    return Err(anyhow!("unexpected probability [{}] in production for {}", probability, lhs));
+                }
+                productions.push(Production::new(lhs, alternative.rhs));
+            }
+        }
+        let start = productions.first().ok_or_else(|| anyhow!("empty grammar"))?.lhs.clone();
+        Ok(Self { start, productions })
+    }
+
+    /// the start symbol
+    pub fn start(&self) -> &str {
+        &self.start
+    }
+
+    pub fn productions(&self) -> &[Production] {
+        &self.productions
+    }
+
+    /// the productions with the given left hand side
+    pub fn productions_for<'a>(&'a self, lhs: &'a str) -> impl Iterator<Item=&'a Production> + 'a {
+        self.productions.iter().filter(move |production| production.lhs == lhs)
+    }
+
+    /// the nonterminals that can be rewritten to nothing
+    pub fn nullable(&self) -> HashSet<&str> {
+        let mut nullable = HashSet::new();
+        loop {
+            let before = nullable.len();
+            for production in &self.productions {
+                let all_nullable = production.rhs.iter().all(|symbol| match symbol {
+                    Symbol::Nonterminal(name) => nullable.contains(name.as_str()),
+                    Symbol::Terminal(_) => false,
+                });
+                if all_nullable {
+                    nullable.insert(production.lhs.as_str());
+                }
+            }
+            if nullable.len() == before {
+                return nullable;
+            }
+        }
+    }
+}
+
+/// the right hand side of a production in a grammar text, with the optional probability in square brackets
+pub(crate) struct Alternative {
+    pub(crate) rhs: Vec<Symbol>,
+    pub(crate) probability: Option<f64>,
+}
+
+/// Parses the lines of a grammar text into a left hand side with its alternatives
+pub(crate) fn parse_lines(text: &str) -> anyhow::Result<Vec<(&str, Vec<Alternative>)>> {
+    let mut rules = Vec::new();
+    for line in text.lines() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (lhs, rhs) = line.split_once("->").ok_or_else(|| anyhow!("expected '->' in {}", line))?;
+        let lhs = lhs.trim();
+        if lhs.is_empty() || lhs.contains(char::is_whitespace) {
+            return Err(anyhow!("invalid left hand side in {}", line));
+        }
+        rules.push((lhs, parse_alternatives(rhs)?));
+    }
+    Ok(rules)
+}
+
+/// removes a comment, unless the # is quoted
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (c, quote) {
+            ('#', None) => return &line[..index],
+            ('\'' | '"', None) => quote = Some(c),
+            (_, Some(q)) if c == q => quote = None,
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_alternatives(rhs: &str) -> anyhow::Result<Vec<Alternative>> {
+    let mut alternatives = Vec::new();
+    let mut current = Alternative { rhs: Vec::new(), probability: None };
+    let mut chars = rhs.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            _ if c.is_whitespace() => {}
+            '|' => alternatives.push(std::mem::replace(&mut current, Alternative { rhs: Vec::new(), probability: None })),
+            '\'' | '"' => {
+                let end = chars.by_ref().find(|&(_, d)| d == c).ok_or_else(|| anyhow!("unterminated terminal in {}", rhs))?.0;
+                current.rhs.push(Symbol::Terminal(rhs[start + 1..end].to_string()));
+            }
+            '[' => {
+                let end = chars.by_ref().find(|&(_, d)| d == ']').ok_or_else(|| anyhow!("unterminated probability in {}", rhs))?.0;
+                let probability = rhs[start + 1..end].trim().parse()
+                    .map_err(|_| anyhow!("invalid probability in {}", rhs))?;
+                current.probability = Some(probability);
+            }
+            _ => {
+                let mut end = rhs.len();
+                while let Some(&(index, d)) = chars.peek() {
+                    if d.is_whitespace() || matches!(d, '|' | '\'' | '"' | '[') {
+                        end = index;
+                        break;
+                    }
+                    chars.next();
+                }
+                current.rhs.push(Symbol::Nonterminal(rhs[start..end].to_string()));
+            }
+        }
+    }
+    alternatives.push(current);
+    Ok(alternatives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_text() {
+        let grammar = Grammar::from_text("
+            S -> NP VP  # a sentence
+            NP -> Det N | 'I' | \"#\"
+            Det ->
+        ").unwrap();
+        assert_eq!(grammar.start(), "S");
+        let productions: Vec<String> = grammar.productions().iter().map(|p| p.to_string()).collect();
+        assert_eq!(productions, vec!["S -> NP VP", "NP -> Det N", "NP -> 'I'", "NP -> '#'", "Det ->"]);
+        assert_eq!(grammar.productions_for("NP").count(), 3);
+    }
+
+    #[test]
+    fn test_nullable() {
+        let grammar = Grammar::from_text("S -> A B\nA -> | 'a'\nB -> A A | 'b'\nC -> 'c'").unwrap();
+        let mut nullable: Vec<&str> = grammar.nullable().into_iter().collect();
+        nullable.sort();
+        assert_eq!(nullable, vec!["A", "B", "S"]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Grammar::from_text("").is_err());
+        assert!(Grammar::from_text("S NP VP").is_err());
+        assert!(Grammar::from_text("S -> 'a").is_err());
+        assert!(Grammar::from_text("S -> NP [0.5]").is_err());
+    }
+}
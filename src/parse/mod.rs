@@ -0,0 +1,12 @@
+use crate::tree::Tree;
+
+pub mod earley;
+pub mod grammar;
+pub mod pcfg;
+pub mod viterbi;
+
+/// A parser finds the syntactic structure of a sentence
+pub trait Parser {
+    /// all parse trees for the tokens, with the start symbol of the grammar as root and the tokens as leaves
+    fn parse(&self, tokens: &[&str]) -> Vec<Tree<String>>;
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::fs;
+
+use anyhow::anyhow;
+
+use crate::parse::grammar::{parse_lines, Grammar, Production, Symbol};
+use crate::tree::{Child, Tree};
+
+/// the probabilities of the productions for a left hand side may be off from 1 by this much
+const EPSILON: f64 = 0.01;
+
+/// A probabilistic context free grammar: a context free grammar with a probability for every production.
+/// For every left hand side, the probabilities of its productions sum to 1.
+///
+/// ```
+/// use rltk::parse::pcfg::ProbabilisticGrammar;
+///
+/// let grammar = ProbabilisticGrammar::from_text("
+///     S -> NP VP [1.0]
+///     NP -> 'John' [0.6] | 'Mary' [0.4]
+///     VP -> 'runs' [1.0]
+/// ").unwrap();
+/// assert_eq!(grammar.grammar().start(), "S");
+/// assert_eq!(grammar.probabilities(), &[1.0, 0.6, 0.4, 1.0]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProbabilisticGrammar {
+    grammar: Grammar,
+    probabilities: Vec<f64>,
+}
+
+impl ProbabilisticGrammar {
+    /// Fails if a probability is not in 0..=1, or if the probabilities for a left hand side do not sum to 1
+    pub fn new(start: &str, productions: Vec<(Production, f64)>) -> anyhow::Result<Self> {
+        let mut totals: HashMap<&str, f64> = HashMap::new();
+        for (production, probability) in &productions {
+            if !(0.0..=1.0).contains(probability) {
+                return Err(anyhow!("invalid probability {} for {}", probability, production));
+            }
+            *totals.entry(production.lhs.as_str()).or_default() += probability;
+        }
+        if let Some((lhs, total)) = totals.iter().find(|(_, total)| (*total - 1.0).abs() > EPSILON) {
+            return Err(anyhow!("probabilities for {} sum to {}", lhs, total));
+        }
+        let (productions, probabilities) = productions.into_iter().unzip();
+        Ok(Self { grammar: Grammar::new(start, productions), probabilities })
+    }
+
+    /// Reads a grammar from a file, in the format of [ProbabilisticGrammar::from_text]
+    pub fn from_file(filename: &str) -> anyhow::Result<Self> {
+        Self::from_text(&fs::read_to_string(filename)?)
+    }
+
+    /// Reads a grammar in the format of [Grammar::from_text], with the probability of every alternative
+    /// in square brackets after it, like `NP -> Det N [0.7] | 'I' [0.3]`
+    pub fn from_text(text: &str) -> anyhow::Result<Self> {
+        let mut productions = Vec::new();
+        for (lhs, alternatives) in parse_lines(text)? {
+            for alternative in alternatives {
+                let probability = alternative.probability
+                    .ok_or_else(|| anyhow!("missing probability in production for {}", lhs))?;
+                productions.push((Production::new(lhs, alternative.rhs), probability));
+            }
+        }
+        let start = productions.first().ok_or_else(|| anyhow!("empty grammar"))?.0.lhs.clone();
+        Self::new(&start, productions)
+    }
+
+    /// the grammar without probabilities
+    pub fn grammar(&self) -> &Grammar {
+        &self.grammar
+    }
+
+    /// the probabilities of the productions, in the order of the productions of the grammar
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    /// the productions with their probabilities
+    pub fn productions(&self) -> impl Iterator<Item=(&Production, f64)> {
+        self.grammar.productions().iter().zip(self.probabilities.iter().copied())
+    }
+}
+
+/// Induces a probabilistic grammar from trees, like those of a treebank.
+/// Every node in the trees is an occurrence of a production from its label to the labels of its subtrees and its leaves.
+/// The probability of a production is its relative frequency among the productions with the same left hand side.
+///
+/// ```
+/// use rltk::parse::pcfg::induce_pcfg;
+/// use rltk::tree::Tree;
+///
+/// let trees = Tree::parse_all("(S (NP John) (VP runs)) (S (NP Mary) (VP runs))").unwrap();
+/// let grammar = induce_pcfg("S", &trees);
+/// let productions: Vec<String> = grammar.productions().map(|(p, probability)| format!("{} [{}]", p, probability)).collect();
+/// assert_eq!(productions, vec!["S -> NP VP [1]", "NP -> 'John' [0.5]", "VP -> 'runs' [1]", "NP -> 'Mary' [0.5]"]);
+/// ```
+pub fn induce_pcfg(start: &str, trees: &[Tree<String>]) -> ProbabilisticGrammar {
+    let mut productions: Vec<Production> = Vec::new();
+    let mut counts: HashMap<Production, usize> = HashMap::new();
+    let mut lhs_counts: HashMap<String, usize> = HashMap::new();
+    for tree in trees {
+        for production in tree_productions(tree) {
+            *lhs_counts.entry(production.lhs.clone()).or_default() += 1;
+            let count = counts.entry(production.clone()).or_default();
+            if *count == 0 {
+                productions.push(production);
+            }
+            *count += 1;
+        }
+    }
+    let probabilities = productions.iter()
+        .map(|production| counts[production] as f64 / lhs_counts[&production.lhs] as f64)
+        .collect();
+    ProbabilisticGrammar { grammar: Grammar::new(start, productions), probabilities }
+}
+
+/// the productions used in a tree, in pre-order
+fn tree_productions(tree: &Tree<String>) -> Vec<Production> {
+    let mut productions = Vec::new();
+    for subtree in tree.subtrees(|_| true) {
+        let rhs = subtree.children.iter()
+            .map(|child| match child {
+                Child::Tree(child) => Symbol::Nonterminal(child.label.clone()),
+                Child::Leaf(leaf) => Symbol::Terminal(leaf.clone()),
+            })
+            .collect();
+        productions.push(Production::new(&subtree.label, rhs));
+    }
+    productions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_text() {
+        let grammar = ProbabilisticGrammar::from_text("S -> A [0.25] | 'b' [0.75]\nA -> [1.0]").unwrap();
+        let productions: Vec<(String, f64)> = grammar.productions().map(|(p, probability)| (p.to_string(), probability)).collect();
+        assert_eq!(productions, vec![("S -> A".to_string(), 0.25), ("S -> 'b'".to_string(), 0.75), ("A ->".to_string(), 1.0)]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(ProbabilisticGrammar::from_text("S -> 'a'").is_err());
+        assert!(ProbabilisticGrammar::from_text("S -> 'a' [0.5] | 'b' [0.4]").is_err());
+        assert!(ProbabilisticGrammar::from_text("S -> 'a' [1.5] | 'b' [-0.5]").is_err());
+        assert!(ProbabilisticGrammar::from_text("S -> 'a' [x]").is_err());
+        // within the margin
+        assert!(ProbabilisticGrammar::from_text("S -> 'a' [0.333] | 'b' [0.333] | 'c' [0.333]").is_ok());
+    }
+
+    #[test]
+    fn test_induce() {
+        let trees = Tree::parse_all("
+            (S (NP (DT the) (NN dog)) (VP (VBD saw) (NP (DT a) (NN cat))))
+            (S (NP (NNP John)) (VP (VBD ran)))
+        ").unwrap();
+        let grammar = induce_pcfg("S", &trees);
+        let probability = |production: &str| grammar.productions()
+            .find(|(p, _)| p.to_string() == production)
+            .map(|(_, probability)| probability);
+        assert_eq!(probability("S -> NP VP"), Some(1.0));
+        assert_eq!(probability("NP -> DT NN"), Some(2.0 / 3.0));
+        assert_eq!(probability("NP -> NNP"), Some(1.0 / 3.0));
+        assert_eq!(probability("VP -> VBD"), Some(0.5));
+        assert_eq!(probability("DT -> 'the'"), Some(0.5));
+        assert_eq!(grammar.grammar().productions().len(), 12);
+    }
+}
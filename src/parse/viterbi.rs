@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use crate::parse::Parser;
+use crate::parse::grammar::Symbol;
+use crate::parse::pcfg::ProbabilisticGrammar;
+use crate::tree::{Child, Tree};
+
+/// Finds the most probable parse tree for a probabilistic grammar.
+///
+/// Bottom up, for ever larger parts of the tokens, it keeps the most probable tree for every nonterminal that covers that part.
+///
+/// ```
+/// use rltk::parse::pcfg::ProbabilisticGrammar;
+/// use rltk::parse::viterbi::ViterbiParser;
+///
+/// let grammar = ProbabilisticGrammar::from_text("
+///     S -> NP VP [1.0]
+///     VP -> V NP [0.6] | VP PP [0.4]
+///     NP -> Det N [0.5] | NP PP [0.2] | 'I' [0.3]
+///     PP -> P NP [1.0]
+///     Det -> 'the' [1.0]
+///     N -> 'man' [0.5] | 'telescope' [0.5]
+///     V -> 'saw' [1.0]
+///     P -> 'with' [1.0]
+/// ").unwrap();
+/// let parser = ViterbiParser::new(&grammar);
+/// let (tree, probability) = parser.best_parse(&["I", "saw", "the", "man", "with", "the", "telescope"]).unwrap();
+/// assert_eq!(tree.to_string(), "(S (NP I) (VP (VP (V saw) (NP (Det the) (N man))) (PP (P with) (NP (Det the) (N telescope)))))");
+/// assert!((probability - 0.0045).abs() < 1e-9);
+/// ```
+pub struct ViterbiParser<'g> {
+    grammar: &'g ProbabilisticGrammar,
+}
+
+/// the most probable tree for a nonterminal covering a part of the tokens, with its log probability
+type Best = HashMap<(String, usize, usize), (f64, Tree<String>)>;
+
+impl<'g> ViterbiParser<'g> {
+    pub fn new(grammar: &'g ProbabilisticGrammar) -> Self {
+        Self { grammar }
+    }
+
+    /// The most probable tree for the tokens, with its probability. None if the grammar does not cover the tokens
+    pub fn best_parse(&self, tokens: &[&str]) -> Option<(Tree<String>, f64)> {
+        let mut best: Best = HashMap::new();
+        for length in 0..=tokens.len() {
+            for start in 0..=tokens.len() - length {
+                let end = start + length;
+                // unary and empty productions build on trees for the same part, so repeat until nothing improves
+                loop {
+                    let mut improved = false;
+                    for (production, probability) in self.grammar.productions() {
+                        if probability == 0.0 {
+                            continue;
+                        }
+                        let Some((log_probability, children)) = best_cover(&best, tokens, &production.rhs, start, end) else {
+                            continue;
+                        };
+                        let log_probability = log_probability + probability.ln();
+                        let key = (production.lhs.clone(), start, end);
+                        if best.get(&key).is_none_or(|(current, _)| log_probability > *current) {
+                            best.insert(key, (log_probability, Tree::new(&production.lhs, children)));
+                            improved = true;
+                        }
+                    }
+                    if !improved {
+                        break;
+                    }
+                }
+            }
+        }
+        best.remove(&(self.grammar.grammar().start().to_string(), 0, tokens.len()))
+            .map(|(log_probability, tree)| (tree, log_probability.exp()))
+    }
+}
+
+/// the most probable way for the symbols to cover tokens start..end, given the best trees found so far
+fn best_cover(best: &Best, tokens: &[&str], symbols: &[Symbol], start: usize, end: usize) -> Option<(f64, Vec<Child<String>>)> {
+    // for every position, the most probable children for the symbols so far that end there
+    let mut covers: Vec<Option<(f64, Vec<Child<String>>)>> = vec![None; end - start + 1];
+    covers[0] = Some((0.0, Vec::new()));
+    for symbol in symbols {
+        let mut next: Vec<Option<(f64, Vec<Child<String>>)>> = vec![None; end - start + 1];
+        for (from, cover) in covers.iter().enumerate() {
+            let Some((log_probability, children)) = cover else { continue };
+            let position = start + from;
+            let mut extend = |to: usize, log_probability: f64, child: Child<String>| {
+                if next[to - start].as_ref().is_none_or(|(current, _)| log_probability > *current) {
+                    let mut children = children.clone();
+                    children.push(child);
+                    next[to - start] = Some((log_probability, children));
+                }
+            };
+            match symbol {
+                Symbol::Terminal(token) => {
+                    if position < end && tokens[position] == token {
+                        extend(position + 1, *log_probability, Child::Leaf(token.clone()));
+                    }
+                }
+                Symbol::Nonterminal(name) => {
+                    for to in position..=end {
+                        if let Some((tree_log_probability, tree)) = best.get(&(name.clone(), position, to)) {
+                            extend(to, log_probability + tree_log_probability, Child::Tree(tree.clone()));
+                        }
+                    }
+                }
+            }
+        }
+        covers = next;
+    }
+    covers.pop().flatten()
+}
+
+impl Parser for ViterbiParser<'_> {
+    /// the most probable tree, if any
+    fn parse(&self, tokens: &[&str]) -> Vec<Tree<String>> {
+        self.best_parse(tokens).map(|(tree, _)| tree).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::pcfg::induce_pcfg;
+
+    #[test]
+    fn test_prefers_probable_attachment() {
+        let grammar = ProbabilisticGrammar::from_text("
+            S -> NP VP [1.0]
+            VP -> V NP [0.8] | VP PP [0.2]
+            NP -> Det N [0.4] | NP PP [0.3] | 'I' [0.3]
+            PP -> P NP [1.0]
+            Det -> 'the' [1.0]
+            N -> 'man' [0.5] | 'telescope' [0.5]
+            V -> 'saw' [1.0]
+            P -> 'with' [1.0]
+        ").unwrap();
+        let parser = ViterbiParser::new(&grammar);
+        let trees = parser.parse(&["I", "saw", "the", "man", "with", "the", "telescope"]);
+        assert_eq!(trees.len(), 1);
+        assert_eq!(trees[0].to_string(), "(S (NP I) (VP (V saw) (NP (NP (Det the) (N man)) (PP (P with) (NP (Det the) (N telescope))))))");
+    }
+
+    #[test]
+    fn test_no_parse() {
+        let grammar = ProbabilisticGrammar::from_text("S -> 'a' 'b' [1.0]").unwrap();
+        let parser = ViterbiParser::new(&grammar);
+        assert!(parser.best_parse(&["a"]).is_none());
+        assert!(parser.parse(&["b", "a"]).is_empty());
+    }
+
+    #[test]
+    fn test_unary_and_empty_productions() {
+        let grammar = ProbabilisticGrammar::from_text("
+            S -> A B [1.0]
+            A -> [0.5] | 'a' [0.5]
+            B -> C [0.5] | 'b' [0.5]
+            C -> 'b' [1.0]
+        ").unwrap();
+        let parser = ViterbiParser::new(&grammar);
+        let (tree, probability) = parser.best_parse(&["b"]).unwrap();
+        // B -> 'b' and B -> C -> 'b' have the same probability, the first one found is kept
+        assert_eq!(tree.to_string(), "(S (A) (B b))");
+        assert!((probability - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_induced_grammar() {
+        let trees = Tree::parse_all("
+            (S (NP (DT the) (NN dog)) (VP (VBD saw) (NP (DT a) (NN cat))))
+            (S (NP (NNP John)) (VP (VBD saw) (NP (DT the) (NN dog))))
+        ").unwrap();
+        let grammar = induce_pcfg("S", &trees);
+        let parser = ViterbiParser::new(&grammar);
+        let (tree, _) = parser.best_parse(&["John", "saw", "a", "dog"]).unwrap();
+        assert_eq!(tree.to_string(), "(S (NP (NNP John)) (VP (VBD saw) (NP (DT a) (NN dog))))");
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::fs;
+
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::stem::Stemmer;
+
+lazy_static! {
+    static ref RULE: Regex = Regex::new(r"^([a-z]+)(\*?)(\d)([a-z]*)([>.]?)$").unwrap();
+}
+
+/// The Lancaster (Paice/Husk) stemmer: an iterative stemmer that applies rules from a table
+/// until a rule tells it to stop, or no rule applies.
+///
+/// Every rule is written as: the ending reversed, an optional `*` (only apply if the word is still intact),
+/// the number of characters to remove, an optional string to append and `>` to continue or `.` to stop.
+/// For instance `dei3y>` turns -ied into -y, and continues stemming.
+///
+/// ```
+/// use rltk::stem::Stemmer;
+/// use rltk::stem::lancaster::LancasterStemmer;
+///
+/// let stemmer = LancasterStemmer::new();
+/// assert_eq!(stemmer.stem("maximum"), "maxim");
+/// ```
+pub struct LancasterStemmer {
+    // rules by the last letter of their ending
+    rules: HashMap<char, Vec<Rule>>,
+}
+
+struct Rule {
+    ending: String,
+    intact: bool,
+    remove: usize,
+    append: String,
+    stop: bool,
+}
+
+impl LancasterStemmer {
+    /// Stemmer with the default rule table from the Paice/Husk paper
+    pub fn new() -> Self {
+        Self::from_rules(include_str!("../../dat/lancaster_rules.dat")).unwrap()
+    }
+
+    /// Stemmer with rules from a file, one rule per line
+    pub fn from_file(filename: &str) -> anyhow::Result<Self> {
+        Self::from_rules(&fs::read_to_string(filename)?)
+    }
+
+    /// Stemmer with rules from a string, one rule per line. Empty lines are ignored.
+    pub fn from_rules(rules: &str) -> anyhow::Result<Self> {
+        let mut table: HashMap<char, Vec<Rule>> = HashMap::new();
+        for line in rules.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let rule = Rule::parse(line)?;
+            let last_letter = rule.ending.chars().last().unwrap();
+            table.entry(last_letter).or_default().push(rule);
+        }
+        Ok(Self { rules: table })
+    }
+
+    fn apply_rules(&self, word: &str) -> String {
+        let intact_word = word;
+        let mut word = word.to_string();
+        loop {
+            let rules = match word.chars().last().and_then(|last| self.rules.get(&last)) {
+                Some(rules) => rules,
+                None => return word,
+            };
+            let rule = rules.iter().find(|rule| word.ends_with(&rule.ending)
+                && (!rule.intact || word == intact_word)
+                && is_acceptable(&word, rule.remove));
+            match rule {
+                Some(rule) => {
+                    let chars: Vec<char> = word.chars().collect();
+                    word = chars[..chars.len() - rule.remove].iter().collect();
+                    word.push_str(&rule.append);
+                    if rule.stop {
+                        return word;
+                    }
+                }
+                None => return word,
+            }
+        }
+    }
+}
+
+impl Default for LancasterStemmer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rule {
+    fn parse(rule: &str) -> anyhow::Result<Self> {
+        let captures = RULE.captures(rule).ok_or_else(|| anyhow!("invalid lancaster rule: {}", rule))?;
+        Ok(Self {
+            ending: captures[1].chars().rev().collect(),
+            intact: &captures[2] == "*",
+            remove: captures[3].parse()?,
+            append: captures[4].to_string(),
+            stop: &captures[5] == ".",
+        })
+    }
+}
+
+/// A word starting with a vowel should keep at least 2 letters,
+/// a word starting with a consonant at least 3, of which the second or third is a vowel
+fn is_acceptable(word: &str, remove: usize) -> bool {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.len() < remove {
+        return false;
+    }
+    let is_vowel = |c: char| "aeiouy".contains(c);
+    if is_vowel(chars[0]) {
+        chars.len() - remove >= 2
+    } else {
+        chars.len() - remove >= 3 && (is_vowel(chars[1]) || is_vowel(chars[2]))
+    }
+}
+
+impl Stemmer for LancasterStemmer {
+    fn stem(&self, word: &str) -> String {
+        self.apply_rules(&word.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        // examples from the nltk documentation
+        let stemmer = LancasterStemmer::new();
+        let expected = [("maximum", "maxim"), ("presumably", "presum"), ("multiply", "multiply"), ("provision", "provid"),
+            ("owed", "ow"), ("ear", "ear"), ("saying", "say"), ("crying", "cry"), ("string", "string"), ("meant", "meant"),
+            ("cement", "cem")];
+
+        for (word, stem) in expected {
+            assert_eq!(stemmer.stem(word), stem, "stem of {}", word);
+        }
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let stemmer = LancasterStemmer::from_rules("ssen4>\nluf3.\n").unwrap();
+        assert_eq!(stemmer.stem("carefulness"), "care");
+        assert!(LancasterStemmer::from_rules("not a rule").is_err());
+    }
+}
@@ -0,0 +1,17 @@
+pub mod lancaster;
+pub mod regexp;
+pub mod snowball;
+pub mod wordnet;
+
+/// A stemmer reduces a word to its stem, by stripping (inflectional) affixes.
+/// The stem is not necessarily a valid word, as long as related words map onto the same stem.
+///
+/// All stemmers in this module implement this trait, so that they can be swapped in a pipeline.
+pub trait Stemmer {
+    fn stem(&self, word: &str) -> String;
+
+    /// Stems every word in the sequence, lazily
+    fn stem_sequence<'a>(&'a self, sequence: impl Iterator<Item=&'a &'a str> + 'a) -> Box<dyn Iterator<Item=String> + 'a> where Self: Sized {
+        Box::new(sequence.map(|word| self.stem(word)))
+    }
+}
@@ -0,0 +1,57 @@
+use regex::Regex;
+
+use crate::stem::Stemmer;
+
+/// Stemmer that removes every part of a word that matches a regular expression.
+/// Words shorter than `min` (in chars) are left alone.
+///
+/// ```
+/// use rltk::stem::Stemmer;
+/// use rltk::stem::regexp::RegexpStemmer;
+///
+/// let stemmer = RegexpStemmer::new("ing$|s$|e$|able$", 4).unwrap();
+/// assert_eq!(stemmer.stem("cars"), "car");
+/// assert_eq!(stemmer.stem("mass"), "mas");
+/// assert_eq!(stemmer.stem("was"), "was");
+/// ```
+pub struct RegexpStemmer {
+    regexp: Regex,
+    min: usize,
+}
+
+impl RegexpStemmer {
+    pub fn new(pattern: &str, min: usize) -> anyhow::Result<Self> {
+        Ok(Self {
+            regexp: Regex::new(pattern)?,
+            min,
+        })
+    }
+}
+
+impl Stemmer for RegexpStemmer {
+    fn stem(&self, word: &str) -> String {
+        if word.chars().count() < self.min {
+            word.to_string()
+        } else {
+            self.regexp.replace_all(word, "").into_owned()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        let stemmer = RegexpStemmer::new("ing$|s$|e$|able$", 4).unwrap();
+        assert_eq!(stemmer.stem("bee"), "bee");
+        assert_eq!(stemmer.stem("advisable"), "advis");
+        assert_eq!(stemmer.stem("walking"), "walk");
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert!(RegexpStemmer::new("(", 0).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::stem::Stemmer;
+use crate::stem::snowball::{delete_suffix, ends_with, in_region, longest_suffix, preceded_by, preceding, replace_suffix, standard_regions};
+
+/// The Dutch snowball stemmer: https://snowballstem.org/algorithms/dutch/stemmer.html
+#[derive(Default)]
+pub struct DutchStemmer;
+
+impl DutchStemmer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'è')
+}
+
+fn remove_accent(c: char) -> char {
+    match c {
+        'ä' | 'á' => 'a',
+        'ë' | 'é' => 'e',
+        'ï' | 'í' => 'i',
+        'ö' | 'ó' => 'o',
+        'ü' | 'ú' => 'u',
+        _ => c
+    }
+}
+
+impl Stemmer for DutchStemmer {
+    fn stem(&self, word: &str) -> String {
+        let mut word: Vec<char> = word.to_lowercase().chars().map(remove_accent).collect();
+        mark_consonants(&mut word);
+
+        let (r1, r2) = standard_regions(&word, is_vowel);
+        // the region before R1 should contain at least 3 letters
+        let r1 = r1.max(3);
+
+        step1(&mut word, r1);
+        let e_removed = step2(&mut word, r1);
+        step3a(&mut word, r1, r2);
+        step3b(&mut word, r1, r2, e_removed);
+        step4(&mut word);
+
+        word.into_iter().map(|c| match c {
+            'I' => 'i',
+            'Y' => 'y',
+            _ => c
+        }).collect()
+    }
+}
+
+/// initial y, y after a vowel and i between vowels are treated as consonants
+fn mark_consonants(word: &mut [char]) {
+    if word.first() == Some(&'y') {
+        word[0] = 'Y';
+    }
+    for i in 1..word.len() {
+        if word[i] == 'y' && is_vowel(word[i - 1]) {
+            word[i] = 'Y';
+        }
+    }
+    for i in 1..word.len().saturating_sub(1) {
+        if word[i] == 'i' && is_vowel(word[i - 1]) && is_vowel(word[i + 1]) {
+            word[i] = 'I';
+        }
+    }
+}
+
+fn undouble(word: &mut Vec<char>) {
+    if ends_with(word, "kk") || ends_with(word, "dd") || ends_with(word, "tt") {
+        word.pop();
+    }
+}
+
+/// a valid en-ending is a non-vowel, and not gem
+fn remove_en_ending(word: &mut Vec<char>, suffix: &str, r1: usize) -> bool {
+    if in_region(word, suffix, r1) && preceding(word, suffix).is_some_and(|c| !is_vowel(c)) && !preceded_by(word, suffix, "gem") {
+        delete_suffix(word, suffix);
+        undouble(word);
+        true
+    } else {
+        false
+    }
+}
+
+fn step1(word: &mut Vec<char>, r1: usize) {
+    match longest_suffix(word, &["heden", "ene", "en", "se", "s"]) {
+        Some("heden") if in_region(word, "heden", r1) => replace_suffix(word, "heden", "heid"),
+        Some(suffix @ ("ene" | "en")) => {
+            remove_en_ending(word, suffix, r1);
+        }
+        // a valid s-ending is a non-vowel other than j
+        Some(suffix @ ("se" | "s")) if in_region(word, suffix, r1) && preceding(word, suffix).is_some_and(|c| !is_vowel(c) && c != 'j') => {
+            delete_suffix(word, suffix);
+        }
+        _ => {}
+    }
+}
+
+/// returns true if an e was removed
+fn step2(word: &mut Vec<char>, r1: usize) -> bool {
+    if ends_with(word, "e") && in_region(word, "e", r1) && preceding(word, "e").is_some_and(|c| !is_vowel(c)) {
+        word.pop();
+        undouble(word);
+        true
+    } else {
+        false
+    }
+}
+
+fn step3a(word: &mut Vec<char>, r1: usize, r2: usize) {
+    if ends_with(word, "heid") && in_region(word, "heid", r2) && !preceded_by(word, "heid", "c") {
+        delete_suffix(word, "heid");
+        if ends_with(word, "en") {
+            remove_en_ending(word, "en", r1);
+        }
+    }
+}
+
+fn step3b(word: &mut Vec<char>, r1: usize, r2: usize, e_removed: bool) {
+    let suffix = match longest_suffix(word, &["end", "ing", "ig", "lijk", "baar", "bar"]) {
+        Some(suffix) if in_region(word, suffix, r2) => suffix,
+        _ => return,
+    };
+    match suffix {
+        "end" | "ing" => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "ig") && in_region(word, "ig", r2) && !preceded_by(word, "ig", "e") {
+                delete_suffix(word, "ig");
+            } else {
+                undouble(word);
+            }
+        }
+        "ig" if !preceded_by(word, "ig", "e") => delete_suffix(word, "ig"),
+        "lijk" => {
+            delete_suffix(word, "lijk");
+            step2(word, r1);
+        }
+        "baar" => delete_suffix(word, "baar"),
+        "bar" if e_removed => delete_suffix(word, "bar"),
+        _ => {}
+    }
+}
+
+/// undouble vowel: if the word ends with a non-vowel, a double vowel (aa, ee, oo or uu)
+/// and a non-vowel other than I, one of the vowels is removed
+fn step4(word: &mut Vec<char>) {
+    let n = word.len();
+    if n >= 4 {
+        let (c, v1, v2, d) = (word[n - 4], word[n - 3], word[n - 2], word[n - 1]);
+        if !is_vowel(c) && v1 == v2 && matches!(v1, 'a' | 'e' | 'o' | 'u') && !is_vowel(d) && d != 'I' {
+            word.remove(n - 2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        let stemmer = DutchStemmer::new();
+        let expected = [("lichamelijk", "licham"), ("lichamelijke", "licham"), ("lichamelijkheden", "licham"), ("lichten", "licht"),
+            ("lichtgevende", "lichtgev"), ("opgaven", "opgav"), ("maan", "man"), ("kinderen", "kinder"), ("café", "caf")];
+
+        for (word, stem) in expected {
+            assert_eq!(stemmer.stem(word), stem, "stem of {}", word);
+        }
+    }
+}
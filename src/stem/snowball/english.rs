@@ -0,0 +1,265 @@
+use crate::stem::Stemmer;
+use crate::stem::snowball::{delete_suffix, ends_with, in_region, longest_suffix, preceded_by, preceding, region_after_vowel_consonant, replace_suffix, standard_regions};
+
+/// The English (Porter2) stemmer: https://snowballstem.org/algorithms/english/stemmer.html
+#[derive(Default)]
+pub struct EnglishStemmer;
+
+impl EnglishStemmer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const DOUBLES: [&str; 9] = ["bb", "dd", "ff", "gg", "mm", "nn", "pp", "rr", "tt"];
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// words that are stemmed irregularly, or not at all
+fn exception1(word: &str) -> Option<&'static str> {
+    match word {
+        "skis" => Some("ski"),
+        "skies" => Some("sky"),
+        "dying" => Some("die"),
+        "lying" => Some("lie"),
+        "tying" => Some("tie"),
+        "idly" => Some("idl"),
+        "gently" => Some("gentl"),
+        "ugly" => Some("ugli"),
+        "early" => Some("earli"),
+        "only" => Some("onli"),
+        "singly" => Some("singl"),
+        "sky" => Some("sky"),
+        "news" => Some("news"),
+        "howe" => Some("howe"),
+        "atlas" => Some("atlas"),
+        "cosmos" => Some("cosmos"),
+        "bias" => Some("bias"),
+        "andes" => Some("andes"),
+        _ => None,
+    }
+}
+
+/// words that are left alone after step 1a
+const EXCEPTIONS2: [&str; 8] = ["inning", "outing", "canning", "herring", "earring", "proceed", "exceed", "succeed"];
+
+/// a vowel followed by a non-vowel other than w, x or Y and preceded by a non-vowel,
+/// or a vowel at the beginning of the word followed by a non-vowel
+fn ends_in_short_syllable(word: &[char]) -> bool {
+    let n = word.len();
+    if n == 2 {
+        is_vowel(word[0]) && !is_vowel(word[1])
+    } else if n > 2 {
+        !is_vowel(word[n - 3]) && is_vowel(word[n - 2]) && !is_vowel(word[n - 1]) && !matches!(word[n - 1], 'w' | 'x' | 'Y')
+    } else {
+        false
+    }
+}
+
+fn is_short_word(word: &[char], r1: usize) -> bool {
+    r1 >= word.len() && ends_in_short_syllable(word)
+}
+
+fn regions(word: &[char]) -> (usize, usize) {
+    for prefix in ["gener", "commun", "arsen"] {
+        let len = prefix.chars().count();
+        if word.len() >= len && word[..len].iter().copied().eq(prefix.chars()) {
+            return (len, region_after_vowel_consonant(word, len, is_vowel));
+        }
+    }
+    standard_regions(word, is_vowel)
+}
+
+impl Stemmer for EnglishStemmer {
+    fn stem(&self, word: &str) -> String {
+        let word = word.to_lowercase().replace(['\u{2018}', '\u{2019}'], "'");
+        if word.chars().count() <= 2 {
+            return word;
+        }
+        if let Some(exception) = exception1(&word) {
+            return exception.to_string();
+        }
+
+        let mut word: Vec<char> = word.chars().collect();
+        if word[0] == '\'' {
+            word.remove(0);
+        }
+        mark_consonant_y(&mut word);
+        let (r1, r2) = regions(&word);
+
+        step0(&mut word);
+        step1a(&mut word);
+        let current: String = word.iter().collect();
+        if EXCEPTIONS2.contains(&current.as_str()) {
+            return current;
+        }
+        step1b(&mut word, r1);
+        step1c(&mut word);
+        step2(&mut word, r1);
+        step3(&mut word, r1, r2);
+        step4(&mut word, r2);
+        step5(&mut word, r1, r2);
+
+        word.into_iter().map(|c| if c == 'Y' { 'y' } else { c }).collect()
+    }
+}
+
+/// initial y, and y after a vowel are treated as consonants
+fn mark_consonant_y(word: &mut [char]) {
+    if word[0] == 'y' {
+        word[0] = 'Y';
+    }
+    for i in 1..word.len() {
+        if word[i] == 'y' && is_vowel(word[i - 1]) {
+            word[i] = 'Y';
+        }
+    }
+}
+
+fn step0(word: &mut Vec<char>) {
+    if let Some(suffix) = longest_suffix(word, &["'s'", "'s", "'"]) {
+        delete_suffix(word, suffix);
+    }
+}
+
+fn step1a(word: &mut Vec<char>) {
+    match longest_suffix(word, &["sses", "ied", "ies", "us", "ss", "s"]) {
+        Some("sses") => replace_suffix(word, "sses", "ss"),
+        Some(suffix @ ("ied" | "ies")) => {
+            let replacement = if word.len() > 4 { "i" } else { "ie" };
+            replace_suffix(word, suffix, replacement);
+        }
+        Some("s") if word[..word.len().saturating_sub(2)].iter().any(|c| is_vowel(*c)) => delete_suffix(word, "s"),
+        _ => {}
+    }
+}
+
+fn step1b(word: &mut Vec<char>, r1: usize) {
+    match longest_suffix(word, &["eed", "eedly", "ed", "edly", "ing", "ingly"]) {
+        Some(suffix @ ("eed" | "eedly")) if in_region(word, suffix, r1) => replace_suffix(word, suffix, "ee"),
+        Some("eed" | "eedly") | None => {}
+        Some(suffix) => {
+            let stem_len = word.len() - suffix.chars().count();
+            if word[..stem_len].iter().any(|c| is_vowel(*c)) {
+                delete_suffix(word, suffix);
+                if ends_with(word, "at") || ends_with(word, "bl") || ends_with(word, "iz") {
+                    word.push('e');
+                } else if DOUBLES.iter().any(|double| ends_with(word, double)) {
+                    word.pop();
+                } else if is_short_word(word, r1) {
+                    word.push('e');
+                }
+            }
+        }
+    }
+}
+
+fn step1c(word: &mut [char]) {
+    let n = word.len();
+    if n > 2 && matches!(word[n - 1], 'y' | 'Y') && !is_vowel(word[n - 2]) {
+        word[n - 1] = 'i';
+    }
+}
+
+const STEP2: [(&str, &str); 24] = [
+    ("tional", "tion"), ("enci", "ence"), ("anci", "ance"), ("abli", "able"), ("entli", "ent"),
+    ("izer", "ize"), ("ization", "ize"), ("ational", "ate"), ("ation", "ate"), ("ator", "ate"),
+    ("alism", "al"), ("aliti", "al"), ("alli", "al"), ("fulness", "ful"), ("ousli", "ous"),
+    ("ousness", "ous"), ("iveness", "ive"), ("iviti", "ive"), ("biliti", "ble"), ("bli", "ble"),
+    ("ogi", "og"), ("fulli", "ful"), ("lessli", "less"), ("li", ""),
+];
+
+fn step2(word: &mut Vec<char>, r1: usize) {
+    let suffixes: Vec<&str> = STEP2.iter().map(|(suffix, _)| *suffix).collect();
+    if let Some(suffix) = longest_suffix(word, &suffixes) {
+        if !in_region(word, suffix, r1) {
+            return;
+        }
+        let applies = match suffix {
+            "ogi" => preceded_by(word, suffix, "l"),
+            "li" => matches!(preceding(word, suffix), Some('c' | 'd' | 'e' | 'g' | 'h' | 'k' | 'm' | 'n' | 'r' | 't')),
+            _ => true,
+        };
+        if applies {
+            let (_, replacement) = STEP2.iter().find(|(s, _)| *s == suffix).unwrap();
+            replace_suffix(word, suffix, replacement);
+        }
+    }
+}
+
+const STEP3: [(&str, &str); 9] = [
+    ("tional", "tion"), ("ational", "ate"), ("alize", "al"), ("icate", "ic"), ("iciti", "ic"),
+    ("ical", "ic"), ("ful", ""), ("ness", ""), ("ative", ""),
+];
+
+fn step3(word: &mut Vec<char>, r1: usize, r2: usize) {
+    let suffixes: Vec<&str> = STEP3.iter().map(|(suffix, _)| *suffix).collect();
+    if let Some(suffix) = longest_suffix(word, &suffixes) {
+        let region = if suffix == "ative" { r2 } else { r1 };
+        if in_region(word, suffix, region) {
+            let (_, replacement) = STEP3.iter().find(|(s, _)| *s == suffix).unwrap();
+            replace_suffix(word, suffix, replacement);
+        }
+    }
+}
+
+const STEP4: [&str; 18] = ["al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent",
+    "ism", "ate", "iti", "ous", "ive", "ize", "ion"];
+
+fn step4(word: &mut Vec<char>, r2: usize) {
+    if let Some(suffix) = longest_suffix(word, &STEP4) {
+        if in_region(word, suffix, r2) && (suffix != "ion" || matches!(preceding(word, suffix), Some('s' | 't'))) {
+            delete_suffix(word, suffix);
+        }
+    }
+}
+
+fn step5(word: &mut Vec<char>, r1: usize, r2: usize) {
+    if ends_with(word, "e") {
+        if in_region(word, "e", r2) || (in_region(word, "e", r1) && !ends_in_short_syllable(&word[..word.len() - 1])) {
+            word.pop();
+        }
+    } else if ends_with(word, "l") && in_region(word, "l", r2) && preceded_by(word, "l", "l") {
+        word.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        // sample from the snowball vocabulary
+        let stemmer = EnglishStemmer::new();
+        let expected = [("consign", "consign"), ("consigned", "consign"), ("consigning", "consign"), ("consignment", "consign"),
+            ("consistency", "consist"), ("consistently", "consist"), ("consolation", "consol"), ("consolatory", "consolatori"),
+            ("consolidated", "consolid"), ("consolingly", "consol"), ("conspicuously", "conspicu"), ("conspiracy", "conspiraci"),
+            ("constable", "constabl"), ("constancy", "constanc"), ("knackeries", "knackeri"), ("kneeling", "kneel"),
+            ("knightly", "knight"), ("knitting", "knit"), ("knives", "knive"), ("knocker", "knocker"),
+            ("generously", "generous"), ("happiness", "happi"), ("caresses", "caress"), ("ponies", "poni"),
+            ("dying", "die"), ("innings", "inning"), ("hopping", "hop"), ("hoping", "hope"), ("'tis", "tis")];
+
+        for (word, stem) in expected {
+            assert_eq!(stemmer.stem(word), stem, "stem of {}", word);
+        }
+    }
+
+    #[test]
+    fn test_short_words_are_untouched() {
+        let stemmer = EnglishStemmer::new();
+        assert_eq!(stemmer.stem("is"), "is");
+        assert_eq!(stemmer.stem("Hi"), "hi");
+    }
+
+    #[test]
+    fn test_apostrophes_only_leave_short_words() {
+        let stemmer = EnglishStemmer::new();
+        assert_eq!(stemmer.stem("'s'"), "s");
+        assert_eq!(stemmer.stem("''s'"), "");
+        assert_eq!(stemmer.stem("s"), "s");
+        assert_eq!(stemmer.stem("'"), "'");
+    }
+}
@@ -0,0 +1,313 @@
+use crate::stem::Stemmer;
+use crate::stem::snowball::{delete_suffix, ends_with, in_region, longest_suffix, preceded_by, preceding, replace_suffix, standard_regions};
+
+/// The French snowball stemmer: https://snowballstem.org/algorithms/french/stemmer.html
+#[derive(Default)]
+pub struct FrenchStemmer;
+
+impl FrenchStemmer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'â' | 'à' | 'ë' | 'é' | 'ê' | 'è' | 'ï' | 'î' | 'ô' | 'û' | 'ù')
+}
+
+const STEP1: [&str; 43] = ["ance", "iqUe", "isme", "able", "iste", "eux", "ances", "iqUes", "ismes", "ables", "istes",
+    "atrice", "ateur", "ation", "atrices", "ateurs", "ations", "logie", "logies", "usion", "ution", "usions", "utions",
+    "ence", "ences", "ement", "ements", "ité", "ités", "if", "ive", "ifs", "ives", "eaux", "aux", "euse", "euses",
+    "issement", "issements", "amment", "emment", "ment", "ments"];
+
+const STEP2A: [&str; 35] = ["îmes", "ît", "îtes", "i", "ie", "ies", "ir", "ira", "irai", "iraIent", "irais", "irait",
+    "iras", "irent", "irez", "iriez", "irions", "irons", "iront", "is", "issaIent", "issais", "issait", "issant",
+    "issante", "issantes", "issants", "isse", "issent", "isses", "issez", "issiez", "issions", "issons", "it"];
+
+const STEP2B_DELETE: [&str; 19] = ["é", "ée", "ées", "és", "èrent", "er", "era", "erai", "eraIent", "erais", "erait",
+    "eras", "erez", "eriez", "erions", "erons", "eront", "ez", "iez"];
+
+const STEP2B_DELETE_E: [&str; 18] = ["âmes", "ât", "âtes", "a", "ai", "aIent", "ais", "ait", "ant", "ante", "antes",
+    "ants", "as", "asse", "assent", "asses", "assiez", "assions"];
+
+impl Stemmer for FrenchStemmer {
+    fn stem(&self, word: &str) -> String {
+        let mut word: Vec<char> = word.to_lowercase().chars().collect();
+        mark_consonants(&mut word);
+
+        let (r1, r2) = standard_regions(&word, is_vowel);
+        let rv = rv(&word);
+
+        let (step1_altered, rv_ending_found) = step1(&mut word, r1, r2, rv);
+        let mut altered = step1_altered;
+        if !step1_altered || rv_ending_found {
+            altered = step2a(&mut word, rv) || step2b(&mut word, r2, rv);
+        }
+        if altered {
+            step3(&mut word);
+        } else {
+            step4(&mut word, r2, rv);
+        }
+        step5(&mut word);
+        step6(&mut word);
+
+        word.into_iter().map(|c| match c {
+            'I' => 'i',
+            'U' => 'u',
+            'Y' => 'y',
+            _ => c
+        }).collect()
+    }
+}
+
+/// u or i between vowels, y preceded or followed by a vowel and u after q are treated as consonants
+fn mark_consonants(word: &mut [char]) {
+    for i in 0..word.len() {
+        let before = if i > 0 { Some(word[i - 1]) } else { None };
+        let after = word.get(i + 1).copied();
+        let vowel_before = before.is_some_and(is_vowel);
+        let vowel_after = after.is_some_and(is_vowel);
+        match word[i] {
+            'u' if before == Some('q') => word[i] = 'U',
+            'u' if vowel_before && vowel_after => word[i] = 'U',
+            'i' if vowel_before && vowel_after => word[i] = 'I',
+            'y' if vowel_before || vowel_after => word[i] = 'Y',
+            _ => {}
+        }
+    }
+}
+
+/// If the word begins with two vowels, RV is the region after the third letter,
+/// otherwise the region after the first vowel not at the beginning of the word.
+/// Words starting with par, col or tap are exceptions, RV starts after these prefixes.
+fn rv(word: &[char]) -> usize {
+    if word.len() < 2 {
+        return word.len();
+    }
+    let start: String = word.iter().take(3).collect();
+    if matches!(start.as_str(), "par" | "col" | "tap") || (is_vowel(word[0]) && is_vowel(word[1])) {
+        return 3.min(word.len());
+    }
+    (1..word.len())
+        .find(|i| is_vowel(word[*i]))
+        .map_or(word.len(), |i| i + 1)
+}
+
+/// deletes `before` if it precedes the current end of the word and lies in the region,
+/// otherwise replaces it, if a replacement is given
+fn delete_or_replace(word: &mut Vec<char>, before: &str, region: usize, replacement: Option<&str>) {
+    if ends_with(word, before) {
+        if in_region(word, before, region) {
+            delete_suffix(word, before);
+        } else if let Some(replacement) = replacement {
+            replace_suffix(word, before, replacement);
+        }
+    }
+}
+
+/// standard suffix removal. Returns (word altered, one of the RV endings amment, emment, ment, ments was found)
+fn step1(word: &mut Vec<char>, r1: usize, r2: usize, rv: usize) -> (bool, bool) {
+    let suffix = match longest_suffix(word, &STEP1) {
+        Some(suffix) => suffix,
+        None => return (false, false),
+    };
+    let in_r1 = in_region(word, suffix, r1);
+    let in_r2 = in_region(word, suffix, r2);
+    let in_rv = in_region(word, suffix, rv);
+
+    match suffix {
+        "ance" | "iqUe" | "isme" | "able" | "iste" | "eux" | "ances" | "iqUes" | "ismes" | "ables" | "istes" if in_r2 => {
+            delete_suffix(word, suffix);
+        }
+        "atrice" | "ateur" | "ation" | "atrices" | "ateurs" | "ations" if in_r2 => {
+            delete_suffix(word, suffix);
+            delete_or_replace(word, "ic", r2, Some("iqU"));
+        }
+        "logie" | "logies" if in_r2 => replace_suffix(word, suffix, "log"),
+        "usion" | "ution" | "usions" | "utions" if in_r2 => replace_suffix(word, suffix, "u"),
+        "ence" | "ences" if in_r2 => replace_suffix(word, suffix, "ent"),
+        "ement" | "ements" if in_rv => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "iv") {
+                if in_region(word, "iv", r2) {
+                    delete_suffix(word, "iv");
+                    delete_or_replace(word, "at", r2, None);
+                }
+            } else if ends_with(word, "eus") {
+                if in_region(word, "eus", r2) {
+                    delete_suffix(word, "eus");
+                } else if in_region(word, "eus", r1) {
+                    replace_suffix(word, "eus", "eux");
+                }
+            } else if ends_with(word, "abl") || ends_with(word, "iqU") {
+                delete_or_replace(word, "abl", r2, None);
+                delete_or_replace(word, "iqU", r2, None);
+            } else if ends_with(word, "ièr") || ends_with(word, "Ièr") {
+                for before in ["ièr", "Ièr"] {
+                    if ends_with(word, before) && in_region(word, before, rv) {
+                        replace_suffix(word, before, "i");
+                    }
+                }
+            }
+        }
+        "ité" | "ités" if in_r2 => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "abil") {
+                delete_or_replace(word, "abil", r2, Some("abl"));
+            } else if ends_with(word, "ic") {
+                delete_or_replace(word, "ic", r2, Some("iqU"));
+            } else {
+                delete_or_replace(word, "iv", r2, None);
+            }
+        }
+        "if" | "ive" | "ifs" | "ives" if in_r2 => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "at") && in_region(word, "at", r2) {
+                delete_suffix(word, "at");
+                delete_or_replace(word, "ic", r2, Some("iqU"));
+            }
+        }
+        "eaux" => replace_suffix(word, suffix, "eau"),
+        "aux" if in_r1 => replace_suffix(word, suffix, "al"),
+        "euse" | "euses" => {
+            if in_r2 {
+                delete_suffix(word, suffix);
+            } else if in_r1 {
+                replace_suffix(word, suffix, "eux");
+            } else {
+                return (false, false);
+            }
+        }
+        "issement" | "issements" if in_r1 && preceding(word, suffix).is_some_and(|c| !is_vowel(c)) => {
+            delete_suffix(word, suffix);
+        }
+        "amment" if in_rv => {
+            replace_suffix(word, suffix, "ant");
+            return (false, true);
+        }
+        "emment" if in_rv => {
+            replace_suffix(word, suffix, "ent");
+            return (false, true);
+        }
+        "ment" | "ments" if in_rv => {
+            // preceded by a vowel in RV
+            let len = suffix.chars().count();
+            if word.len() > len && word.len() - len > rv && is_vowel(word[word.len() - len - 1]) {
+                delete_suffix(word, suffix);
+                return (false, true);
+            }
+            return (false, false);
+        }
+        _ => return (false, false),
+    }
+    (true, false)
+}
+
+/// verb suffixes beginning with i, deleted if preceded by a non-vowel in RV
+fn step2a(word: &mut Vec<char>, rv: usize) -> bool {
+    if rv >= word.len() {
+        return false;
+    }
+    if let Some(suffix) = longest_suffix(&word[rv..], &STEP2A) {
+        let len = suffix.chars().count();
+        if word.len() - rv > len && !is_vowel(word[word.len() - len - 1]) {
+            delete_suffix(word, suffix);
+            return true;
+        }
+    }
+    false
+}
+
+/// other verb suffixes
+fn step2b(word: &mut Vec<char>, r2: usize, rv: usize) -> bool {
+    if rv >= word.len() {
+        return false;
+    }
+    let suffixes: Vec<&str> = STEP2B_DELETE.iter().chain(STEP2B_DELETE_E.iter()).chain(["ions"].iter()).copied().collect();
+    match longest_suffix(&word[rv..], &suffixes) {
+        Some("ions") => {
+            if in_region(word, "ions", r2) {
+                delete_suffix(word, "ions");
+                return true;
+            }
+            false
+        }
+        Some(suffix) if STEP2B_DELETE.contains(&suffix) => {
+            delete_suffix(word, suffix);
+            true
+        }
+        Some(suffix) => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "e") && in_region(word, "e", rv) {
+                word.pop();
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+fn step3(word: &mut [char]) {
+    if let Some(last) = word.last_mut() {
+        match *last {
+            'Y' => *last = 'i',
+            'ç' => *last = 'c',
+            _ => {}
+        }
+    }
+}
+
+/// residual suffixes
+fn step4(word: &mut Vec<char>, r2: usize, rv: usize) {
+    if word.len() >= 2 && ends_with(word, "s") && !matches!(preceding(word, "s"), Some('a' | 'i' | 'o' | 'u' | 'è' | 's')) {
+        word.pop();
+    }
+    if rv >= word.len() {
+        return;
+    }
+    match longest_suffix(&word[rv..], &["ion", "ier", "ière", "Ier", "Ière", "e", "ë"]) {
+        Some("ion") if in_region(word, "ion", r2) && word.len() - rv > 3 && matches!(preceding(word, "ion"), Some('s' | 't')) => {
+            delete_suffix(word, "ion");
+        }
+        Some(suffix @ ("ier" | "ière" | "Ier" | "Ière")) => replace_suffix(word, suffix, "i"),
+        Some("e") => delete_suffix(word, "e"),
+        Some("ë") if preceded_by(word, "ë", "gu") => delete_suffix(word, "ë"),
+        _ => {}
+    }
+}
+
+/// undouble
+fn step5(word: &mut Vec<char>) {
+    if ["enn", "onn", "ett", "ell", "eill"].iter().any(|ending| ends_with(word, ending)) {
+        word.pop();
+    }
+}
+
+/// un-accent: an é or è followed by at least one non-vowel at the end of the word, becomes e
+fn step6(word: &mut [char]) {
+    let last_vowel = word.iter().rposition(|c| is_vowel(*c));
+    if let Some(index) = last_vowel {
+        if index + 1 < word.len() && matches!(word[index], 'é' | 'è') {
+            word[index] = 'e';
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        // sample from the snowball vocabulary
+        let stemmer = FrenchStemmer::new();
+        let expected = [("continu", "continu"), ("continua", "continu"), ("continuait", "continu"), ("continuant", "continu"),
+            ("continuation", "continu"), ("continue", "continu"), ("continué", "continu"), ("continuel", "continuel"),
+            ("continuelle", "continuel"), ("continuellement", "continuel"), ("continuelles", "continuel"),
+            ("continuer", "continu"), ("continuez", "continu"), ("continuité", "continu")];
+
+        for (word, stem) in expected {
+            assert_eq!(stemmer.stem(word), stem, "stem of {}", word);
+        }
+    }
+}
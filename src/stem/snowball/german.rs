@@ -0,0 +1,138 @@
+use crate::stem::Stemmer;
+use crate::stem::snowball::{delete_suffix, ends_with, in_region, longest_suffix, preceded_by, preceding, standard_regions};
+
+/// The German snowball stemmer: https://snowballstem.org/algorithms/german/stemmer.html
+#[derive(Default)]
+pub struct GermanStemmer;
+
+impl GermanStemmer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'ä' | 'ö' | 'ü')
+}
+
+impl Stemmer for GermanStemmer {
+    fn stem(&self, word: &str) -> String {
+        let mut word: Vec<char> = word.to_lowercase().replace('ß', "ss").chars().collect();
+        mark_consonants(&mut word);
+
+        let (r1, r2) = standard_regions(&word, is_vowel);
+        // the region before R1 should contain at least 3 letters
+        let r1 = r1.max(3);
+
+        step1(&mut word, r1);
+        step2(&mut word, r1);
+        step3(&mut word, r1, r2);
+
+        word.into_iter().map(|c| match c {
+            'U' | 'ü' => 'u',
+            'Y' => 'y',
+            'ä' => 'a',
+            'ö' => 'o',
+            _ => c
+        }).collect()
+    }
+}
+
+/// u and y between vowels are treated as consonants
+fn mark_consonants(word: &mut [char]) {
+    for i in 1..word.len().saturating_sub(1) {
+        if is_vowel(word[i - 1]) && is_vowel(word[i + 1]) {
+            match word[i] {
+                'u' => word[i] = 'U',
+                'y' => word[i] = 'Y',
+                _ => {}
+            }
+        }
+    }
+}
+
+fn step1(word: &mut Vec<char>, r1: usize) {
+    let suffix = match longest_suffix(word, &["em", "ern", "er", "e", "en", "es", "s"]) {
+        Some(suffix) if in_region(word, suffix, r1) => suffix,
+        _ => return,
+    };
+    match suffix {
+        "e" | "en" | "es" => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "niss") {
+                word.pop();
+            }
+        }
+        // valid s-ending
+        "s" if matches!(preceding(word, suffix), Some('b' | 'd' | 'f' | 'g' | 'h' | 'k' | 'l' | 'm' | 'n' | 'r' | 't')) => {
+            delete_suffix(word, suffix);
+        }
+        "s" => {}
+        _ => delete_suffix(word, suffix),
+    }
+}
+
+fn step2(word: &mut Vec<char>, r1: usize) {
+    let suffix = match longest_suffix(word, &["en", "er", "est", "st"]) {
+        Some(suffix) if in_region(word, suffix, r1) => suffix,
+        _ => return,
+    };
+    if suffix == "st" {
+        // valid st-ending, itself preceded by at least 3 letters
+        let valid_ending = matches!(preceding(word, suffix), Some('b' | 'd' | 'f' | 'g' | 'h' | 'k' | 'l' | 'm' | 'n' | 't'));
+        if valid_ending && word.len() >= 6 {
+            delete_suffix(word, suffix);
+        }
+    } else {
+        delete_suffix(word, suffix);
+    }
+}
+
+fn step3(word: &mut Vec<char>, r1: usize, r2: usize) {
+    let suffix = match longest_suffix(word, &["end", "ung", "ig", "ik", "isch", "lich", "heit", "keit"]) {
+        Some(suffix) if in_region(word, suffix, r2) => suffix,
+        _ => return,
+    };
+    match suffix {
+        "end" | "ung" => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "ig") && in_region(word, "ig", r2) && !preceded_by(word, "ig", "e") {
+                delete_suffix(word, "ig");
+            }
+        }
+        "ig" | "ik" | "isch" if !preceded_by(word, suffix, "e") => delete_suffix(word, suffix),
+        "lich" | "heit" => {
+            delete_suffix(word, suffix);
+            if let Some(before) = longest_suffix(word, &["er", "en"]) {
+                if in_region(word, before, r1) {
+                    delete_suffix(word, before);
+                }
+            }
+        }
+        "keit" => {
+            delete_suffix(word, suffix);
+            if let Some(before) = longest_suffix(word, &["lich", "ig"]) {
+                if in_region(word, before, r2) {
+                    delete_suffix(word, before);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        let stemmer = GermanStemmer::new();
+        let expected = [("aufeinanderfolgenden", "aufeinanderfolg"), ("käufer", "kauf"), ("Häuser", "haus"),
+            ("kategorischen", "kategor"), ("Straßen", "strass"), ("Ergebnisse", "ergebnis")];
+
+        for (word, stem) in expected {
+            assert_eq!(stemmer.stem(word), stem, "stem of {}", word);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+// Stemmers from the Snowball project: https://snowballstem.org/algorithms/
+//
+// The algorithms are described in terms of regions (R1, R2 and RV), that are computed once
+// per word, before any suffix is removed. Here they are kept as the char index where the region starts.
+
+use anyhow::anyhow;
+
+use crate::stem::Stemmer;
+
+pub use dutch::DutchStemmer;
+pub use english::EnglishStemmer;
+pub use french::FrenchStemmer;
+pub use german::GermanStemmer;
+pub use spanish::SpanishStemmer;
+
+mod dutch;
+mod english;
+mod french;
+mod german;
+mod spanish;
+
+/// Snowball stemmer for a language chosen at runtime
+/// ```
+/// use rltk::stem::Stemmer;
+/// use rltk::stem::snowball::SnowballStemmer;
+///
+/// let stemmer = SnowballStemmer::new("dutch").unwrap();
+/// assert_eq!(stemmer.stem("lichamelijk"), "licham");
+/// ```
+pub struct SnowballStemmer {
+    stemmer: Box<dyn Stemmer>,
+}
+
+impl SnowballStemmer {
+    /// Supported languages: english (or porter2), dutch, german, french and spanish
+    pub fn new(language: &str) -> anyhow::Result<Self> {
+        let stemmer: Box<dyn Stemmer> = match language.to_lowercase().as_str() {
+            "english" | "porter2" => Box::new(EnglishStemmer::new()),
+            "dutch" => Box::new(DutchStemmer::new()),
+            "german" => Box::new(GermanStemmer::new()),
+            "french" => Box::new(FrenchStemmer::new()),
+            "spanish" => Box::new(SpanishStemmer::new()),
+            _ => return Err(anyhow!("no snowball stemmer for language {}", language)),
+        };
+        Ok(Self { stemmer })
+    }
+
+    pub fn languages() -> &'static [&'static str] {
+        &["english", "dutch", "german", "french", "spanish"]
+    }
+}
+
+impl Stemmer for SnowballStemmer {
+    fn stem(&self, word: &str) -> String {
+        self.stemmer.stem(word)
+    }
+}
+
+/// R1 is the region after the first non-vowel following a vowel, R2 is the same, but starting from R1
+/// Both are the word length if there is no such non-vowel
+pub(crate) fn standard_regions(word: &[char], is_vowel: impl Fn(char) -> bool) -> (usize, usize) {
+    let r1 = region_after_vowel_consonant(word, 0, &is_vowel);
+    let r2 = region_after_vowel_consonant(word, r1, &is_vowel);
+    (r1, r2)
+}
+
+pub(crate) fn region_after_vowel_consonant(word: &[char], start: usize, is_vowel: impl Fn(char) -> bool) -> usize {
+    for i in start + 1..word.len() {
+        if !is_vowel(word[i]) && is_vowel(word[i - 1]) {
+            return i + 1;
+        }
+    }
+    word.len()
+}
+
+pub(crate) fn ends_with(word: &[char], suffix: &str) -> bool {
+    let len = suffix.chars().count();
+    len <= word.len() && word[word.len() - len..].iter().copied().eq(suffix.chars())
+}
+
+/// returns the longest suffix from the list that the word ends with
+pub(crate) fn longest_suffix<'s>(word: &[char], suffixes: &[&'s str]) -> Option<&'s str> {
+    suffixes.iter()
+        .filter(|suffix| ends_with(word, suffix))
+        .max_by_key(|suffix| suffix.chars().count())
+        .copied()
+}
+
+/// true if the suffix (that the word ends with) lies completely within the region starting at `region`
+pub(crate) fn in_region(word: &[char], suffix: &str, region: usize) -> bool {
+    word.len() >= region + suffix.chars().count()
+}
+
+/// the char before the suffix, if any
+pub(crate) fn preceding(word: &[char], suffix: &str) -> Option<char> {
+    let len = suffix.chars().count();
+    if word.len() > len {
+        Some(word[word.len() - len - 1])
+    } else {
+        None
+    }
+}
+
+/// true if the suffix is preceded by `before`
+pub(crate) fn preceded_by(word: &[char], suffix: &str, before: &str) -> bool {
+    let len = suffix.chars().count();
+    word.len() >= len && ends_with(&word[..word.len() - len], before)
+}
+
+pub(crate) fn replace_suffix(word: &mut Vec<char>, suffix: &str, replacement: &str) {
+    word.truncate(word.len() - suffix.chars().count());
+    word.extend(replacement.chars());
+}
+
+pub(crate) fn delete_suffix(word: &mut Vec<char>, suffix: &str) {
+    replace_suffix(word, suffix, "");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_regions() {
+        // examples from https://snowballstem.org/texts/r1r2.html
+        let is_vowel = |c| "aeiouy".contains(c);
+        let word: Vec<char> = "beautiful".chars().collect();
+        assert_eq!(standard_regions(&word, is_vowel), (5, 7));
+        let word: Vec<char> = "beauty".chars().collect();
+        assert_eq!(standard_regions(&word, is_vowel), (5, 6));
+        let word: Vec<char> = "animadversion".chars().collect();
+        assert_eq!(standard_regions(&word, is_vowel), (2, 4));
+    }
+
+    #[test]
+    fn test_unknown_language() {
+        assert!(SnowballStemmer::new("klingon").is_err());
+    }
+}
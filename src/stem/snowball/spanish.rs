@@ -0,0 +1,220 @@
+use crate::stem::Stemmer;
+use crate::stem::snowball::{delete_suffix, ends_with, in_region, longest_suffix, preceded_by, replace_suffix, standard_regions};
+
+/// The Spanish snowball stemmer: https://snowballstem.org/algorithms/spanish/stemmer.html
+#[derive(Default)]
+pub struct SpanishStemmer;
+
+impl SpanishStemmer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'á' | 'é' | 'í' | 'ó' | 'ú' | 'ü')
+}
+
+fn remove_accent(c: char) -> char {
+    match c {
+        'á' => 'a',
+        'é' => 'e',
+        'í' => 'i',
+        'ó' => 'o',
+        'ú' => 'u',
+        _ => c
+    }
+}
+
+const PRONOUNS: [&str; 13] = ["me", "se", "sela", "selo", "selas", "selos", "la", "le", "lo", "las", "les", "los", "nos"];
+
+const STEP2A: [&str; 12] = ["ya", "ye", "yan", "yen", "yeron", "yendo", "yo", "yó", "yas", "yes", "yais", "yamos"];
+
+const STEP2B: [&str; 92] = ["arían", "arías", "arán", "arás", "aríais", "aría", "aréis", "aríamos", "aremos", "ará", "aré",
+    "erían", "erías", "erán", "erás", "eríais", "ería", "eréis", "eríamos", "eremos", "erá", "eré",
+    "irían", "irías", "irán", "irás", "iríais", "iría", "iréis", "iríamos", "iremos", "irá", "iré",
+    "aba", "ada", "ida", "ía", "ara", "iera", "ad", "ed", "id", "ase", "iese", "aste", "iste", "an", "aban", "ían",
+    "aran", "ieran", "asen", "iesen", "aron", "ieron", "ado", "ido", "ando", "iendo", "ió", "ar", "er", "ir", "as",
+    "abas", "adas", "idas", "ías", "aras", "ieras", "ases", "ieses", "ís", "áis", "abais", "íais", "arais", "ierais",
+    "aseis", "ieseis", "asteis", "isteis", "ados", "idos", "amos", "ábamos", "íamos", "imos", "áramos", "iéramos",
+    "iésemos", "ásemos"];
+
+impl Stemmer for SpanishStemmer {
+    fn stem(&self, word: &str) -> String {
+        let mut word: Vec<char> = word.to_lowercase().chars().collect();
+
+        let (r1, r2) = standard_regions(&word, is_vowel);
+        let rv = rv(&word);
+
+        step0(&mut word, rv);
+        if !step1(&mut word, r1, r2) && !step2a(&mut word, rv) {
+            step2b(&mut word, rv);
+        }
+        step3(&mut word, rv);
+
+        word.into_iter().map(remove_accent).collect()
+    }
+}
+
+/// If the second letter is a consonant, RV is the region after the next following vowel,
+/// or if the first two letters are vowels, RV is the region after the next consonant,
+/// and otherwise (consonant-vowel case) RV is the region after the third letter.
+fn rv(word: &[char]) -> usize {
+    if word.len() < 2 {
+        return word.len();
+    }
+    let found = if !is_vowel(word[1]) {
+        (2..word.len()).find(|i| is_vowel(word[*i]))
+    } else if is_vowel(word[0]) {
+        (2..word.len()).find(|i| !is_vowel(word[*i]))
+    } else {
+        return 3.min(word.len());
+    };
+    found.map_or(word.len(), |i| i + 1)
+}
+
+/// attached pronoun
+fn step0(word: &mut Vec<char>, rv: usize) {
+    let pronoun = match longest_suffix(word, &PRONOUNS) {
+        Some(pronoun) => pronoun,
+        None => return,
+    };
+    let stem_len = word.len() - pronoun.chars().count();
+    let before = &word[..stem_len];
+    let ending = match longest_suffix(before, &["iéndo", "ándo", "ár", "ér", "ír", "ando", "iendo", "ar", "er", "ir", "yendo"]) {
+        Some(ending) if in_region(before, ending, rv) => ending,
+        _ => return,
+    };
+    if ending == "yendo" && !preceded_by(before, ending, "u") {
+        return;
+    }
+    delete_suffix(word, pronoun);
+    let unaccented: String = ending.chars().map(remove_accent).collect();
+    replace_suffix(word, ending, &unaccented);
+}
+
+/// standard suffix removal, returns true if the word was altered
+fn step1(word: &mut Vec<char>, r1: usize, r2: usize) -> bool {
+    let suffix = match longest_suffix(word, &["anza", "anzas", "ico", "ica", "icos", "icas", "ismo", "ismos", "able", "ables",
+        "ible", "ibles", "ista", "istas", "oso", "osa", "osos", "osas", "amiento", "amientos", "imiento", "imientos",
+        "adora", "ador", "ación", "adoras", "adores", "aciones", "ante", "antes", "ancia", "ancias", "logía", "logías",
+        "ución", "uciones", "encia", "encias", "amente", "mente", "idad", "idades", "iva", "ivo", "ivas", "ivos"]) {
+        Some(suffix) => suffix,
+        None => return false,
+    };
+    let region = if suffix == "amente" { r1 } else { r2 };
+    if !in_region(word, suffix, region) {
+        return false;
+    }
+    match suffix {
+        "adora" | "ador" | "ación" | "adoras" | "adores" | "aciones" | "ante" | "antes" | "ancia" | "ancias" => {
+            delete_suffix(word, suffix);
+            delete_in_r2(word, "ic", r2);
+        }
+        "logía" | "logías" => replace_suffix(word, suffix, "log"),
+        "ución" | "uciones" => replace_suffix(word, suffix, "u"),
+        "encia" | "encias" => replace_suffix(word, suffix, "ente"),
+        "amente" => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "iv") {
+                if delete_in_r2(word, "iv", r2) {
+                    delete_in_r2(word, "at", r2);
+                }
+            } else if let Some(before) = longest_suffix(word, &["os", "ic", "ad"]) {
+                delete_in_r2(word, before, r2);
+            }
+        }
+        "mente" => {
+            delete_suffix(word, suffix);
+            if let Some(before) = longest_suffix(word, &["ante", "able", "ible"]) {
+                delete_in_r2(word, before, r2);
+            }
+        }
+        "idad" | "idades" => {
+            delete_suffix(word, suffix);
+            if let Some(before) = longest_suffix(word, &["abil", "ic", "iv"]) {
+                delete_in_r2(word, before, r2);
+            }
+        }
+        "iva" | "ivo" | "ivas" | "ivos" => {
+            delete_suffix(word, suffix);
+            delete_in_r2(word, "at", r2);
+        }
+        _ => delete_suffix(word, suffix),
+    }
+    true
+}
+
+fn delete_in_r2(word: &mut Vec<char>, suffix: &str, r2: usize) -> bool {
+    if ends_with(word, suffix) && in_region(word, suffix, r2) {
+        delete_suffix(word, suffix);
+        true
+    } else {
+        false
+    }
+}
+
+/// verb suffixes beginning with y, deleted if preceded by u (which need not be in RV)
+fn step2a(word: &mut Vec<char>, rv: usize) -> bool {
+    if rv >= word.len() {
+        return false;
+    }
+    match longest_suffix(&word[rv..], &STEP2A) {
+        Some(suffix) if preceded_by(word, suffix, "u") => {
+            delete_suffix(word, suffix);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// other verb suffixes
+fn step2b(word: &mut Vec<char>, rv: usize) {
+    if rv >= word.len() {
+        return;
+    }
+    let suffixes: Vec<&str> = STEP2B.iter().chain(["en", "es", "éis", "emos"].iter()).copied().collect();
+    match longest_suffix(&word[rv..], &suffixes) {
+        Some(suffix @ ("en" | "es" | "éis" | "emos")) => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "gu") {
+                word.pop();
+            }
+        }
+        Some(suffix) => delete_suffix(word, suffix),
+        None => {}
+    }
+}
+
+/// residual suffix
+fn step3(word: &mut Vec<char>, rv: usize) {
+    if rv >= word.len() {
+        return;
+    }
+    match longest_suffix(&word[rv..], &["os", "a", "o", "á", "í", "ó", "e", "é"]) {
+        Some(suffix @ ("e" | "é")) => {
+            delete_suffix(word, suffix);
+            if ends_with(word, "gu") && in_region(word, "u", rv) {
+                word.pop();
+            }
+        }
+        Some(suffix) => delete_suffix(word, suffix),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        let stemmer = SpanishStemmer::new();
+        let expected = [("chica", "chic"), ("chicas", "chic"), ("chico", "chic"), ("cantaba", "cant"), ("comiendo", "com"),
+            ("comiéndolo", "com"), ("rápidamente", "rapid"), ("nacionalidades", "nacional")];
+
+        for (word, stem) in expected {
+            assert_eq!(stemmer.stem(word), stem, "stem of {}", word);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::anyhow;
+
+/// Part of speech, as distinguished by the lemmatizer
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Pos {
+    Noun,
+    Verb,
+    Adjective,
+    Adverb,
+}
+
+impl Pos {
+    pub const ALL: [Pos; 4] = [Pos::Noun, Pos::Verb, Pos::Adjective, Pos::Adverb];
+
+    /// Maps a Penn Treebank tag (NN, VBD, JJR, RB etc) onto a part of speech
+    pub fn from_penn_tag(tag: &str) -> Option<Self> {
+        if tag.starts_with("NN") {
+            Some(Pos::Noun)
+        } else if tag.starts_with("VB") {
+            Some(Pos::Verb)
+        } else if tag.starts_with("JJ") {
+            Some(Pos::Adjective)
+        } else if tag.starts_with("RB") {
+            Some(Pos::Adverb)
+        } else {
+            None
+        }
+    }
+
+    /// the suffix WordNet uses for its data files (index.noun, verb.exc etc)
+    fn file_suffix(&self) -> &'static str {
+        match self {
+            Pos::Noun => "noun",
+            Pos::Verb => "verb",
+            Pos::Adjective => "adj",
+            Pos::Adverb => "adv",
+        }
+    }
+}
+
+/// The lemmatizer checks every candidate lemma against a lexicon
+pub trait Lexicon {
+    /// true if the lemma is known for the part of speech
+    fn contains(&self, lemma: &str, pos: Pos) -> bool;
+
+    /// irregular forms, like `geese` -> `goose`. Empty if the form is not an exception
+    fn exceptions(&self, form: &str, pos: Pos) -> &[String];
+}
+
+/// Lexicon read from plain text files in the WordNet format:
+/// * index files (index.noun, index.verb, ..): a lemma per line, as the first field. Lines starting with a space are ignored
+/// * exception files (noun.exc, verb.exc, ..): an inflected form per line, followed by its lemmas
+///
+/// A plain list of words, one per line, is a valid index file too.
+#[derive(Default)]
+pub struct WordNetLexicon {
+    lemmas: HashMap<Pos, HashSet<String>>,
+    exceptions: HashMap<Pos, HashMap<String, Vec<String>>>,
+}
+
+impl WordNetLexicon {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads index.{noun,verb,adj,adv} and {noun,verb,adj,adv}.exc from a WordNet dict directory.
+    /// Missing exception files are allowed, missing index files are not.
+    pub fn from_dir(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let dir = dir.as_ref();
+        let mut lexicon = Self::new();
+        for pos in Pos::ALL {
+            let index = dir.join(format!("index.{}", pos.file_suffix()));
+            lexicon.load_index(&index, pos)?;
+            let exceptions = dir.join(format!("{}.exc", pos.file_suffix()));
+            if exceptions.exists() {
+                lexicon.load_exceptions(&exceptions, pos)?;
+            }
+        }
+        Ok(lexicon)
+    }
+
+    pub fn load_index(&mut self, filename: impl AsRef<Path>, pos: Pos) -> anyhow::Result<()> {
+        let filename = filename.as_ref();
+        let contents = fs::read_to_string(filename).map_err(|e| anyhow!("cannot read {}: {}", filename.display(), e))?;
+        self.add_index(&contents, pos);
+        Ok(())
+    }
+
+    pub fn load_exceptions(&mut self, filename: impl AsRef<Path>, pos: Pos) -> anyhow::Result<()> {
+        let filename = filename.as_ref();
+        let contents = fs::read_to_string(filename).map_err(|e| anyhow!("cannot read {}: {}", filename.display(), e))?;
+        self.add_exceptions(&contents, pos);
+        Ok(())
+    }
+
+    /// Adds the lemmas from the contents of an index file
+    pub fn add_index(&mut self, contents: &str, pos: Pos) {
+        for line in contents.lines().filter(|line| !line.starts_with(' ')) {
+            if let Some(lemma) = line.split_whitespace().next() {
+                self.add_lemma(lemma, pos);
+            }
+        }
+    }
+
+    /// Adds the irregular forms from the contents of an exception file
+    pub fn add_exceptions(&mut self, contents: &str, pos: Pos) {
+        for line in contents.lines() {
+            let mut fields = line.split_whitespace();
+            if let Some(form) = fields.next() {
+                let lemmas: Vec<&str> = fields.collect();
+                self.add_exception(form, &lemmas, pos);
+            }
+        }
+    }
+
+    pub fn add_lemma(&mut self, lemma: &str, pos: Pos) {
+        self.lemmas.entry(pos).or_default().insert(lemma.to_string());
+    }
+
+    pub fn add_exception(&mut self, form: &str, lemmas: &[&str], pos: Pos) {
+        let known = self.exceptions.entry(pos).or_default().entry(form.to_string()).or_default();
+        known.extend(lemmas.iter().map(|lemma| lemma.to_string()));
+    }
+}
+
+impl Lexicon for WordNetLexicon {
+    fn contains(&self, lemma: &str, pos: Pos) -> bool {
+        self.lemmas.get(&pos).is_some_and(|lemmas| lemmas.contains(lemma))
+    }
+
+    fn exceptions(&self, form: &str, pos: Pos) -> &[String] {
+        self.exceptions.get(&pos)
+            .and_then(|exceptions| exceptions.get(form))
+            .map_or(&[], |lemmas| lemmas.as_slice())
+    }
+}
+
+/// Lemmatizer in the style of WordNet's morphy: detaches suffixes according to rules for
+/// the part of speech, and keeps only the candidates that the lexicon knows.
+/// Unlike a stemmer, it returns real words, or the word itself if no lemma is found.
+///
+/// ```
+/// use rltk::stem::wordnet::{Lemmatizer, Pos, WordNetLexicon};
+///
+/// let mut lexicon = WordNetLexicon::new();
+/// lexicon.add_index("church\nbox\ngoose\n", Pos::Noun);
+/// lexicon.add_exceptions("geese goose\n", Pos::Noun);
+///
+/// let lemmatizer = Lemmatizer::new(lexicon);
+/// assert_eq!(lemmatizer.lemmatize("churches", Pos::Noun), "church");
+/// assert_eq!(lemmatizer.lemmatize("geese", Pos::Noun), "goose");
+/// assert_eq!(lemmatizer.lemmatize("aardvarks", Pos::Noun), "aardvarks");
+/// ```
+pub struct Lemmatizer<L: Lexicon> {
+    lexicon: L,
+    substitutions: HashMap<Pos, Vec<(String, String)>>,
+}
+
+impl<L: Lexicon> Lemmatizer<L> {
+    /// Lemmatizer with the detachment rules for English
+    pub fn new(lexicon: L) -> Self {
+        let mut lemmatizer = Self {
+            lexicon,
+            substitutions: HashMap::new(),
+        };
+        let rules: [(Pos, &[(&str, &str)]); 3] = [
+            (Pos::Noun, &[("s", ""), ("ses", "s"), ("ves", "f"), ("xes", "x"), ("zes", "z"), ("ches", "ch"), ("shes", "sh"),
+                ("men", "man"), ("ies", "y")]),
+            (Pos::Verb, &[("s", ""), ("ies", "y"), ("es", "e"), ("es", ""), ("ed", "e"), ("ed", ""), ("ing", "e"), ("ing", "")]),
+            (Pos::Adjective, &[("er", ""), ("est", ""), ("er", "e"), ("est", "e")]),
+        ];
+        for (pos, substitutions) in rules {
+            lemmatizer.set_substitutions(pos, substitutions).expect("the English rules do not lengthen words");
+        }
+        lemmatizer
+    }
+
+    /// Replaces the detachment rules for a part of speech, as (suffix, replacement) pairs.
+    /// Fails if a replacement is longer than its suffix, as the rules could then be applied forever
+    pub fn set_substitutions(&mut self, pos: Pos, substitutions: &[(&str, &str)]) -> anyhow::Result<()> {
+        if let Some((suffix, replacement)) = substitutions.iter().find(|(suffix, replacement)| replacement.len() > suffix.len()) {
+            return Err(anyhow!("replacement {} is longer than suffix {}", replacement, suffix));
+        }
+        self.substitutions.insert(pos, substitutions.iter()
+            .map(|(suffix, replacement)| (suffix.to_string(), replacement.to_string()))
+            .collect());
+        Ok(())
+    }
+
+    /// All lemmas for the form that are in the lexicon, like WordNet's morphy.
+    /// Exceptions are checked first, then the rules are applied until there is a match.
+    pub fn morphy(&self, form: &str, pos: Pos) -> Vec<String> {
+        let exceptions = self.lexicon.exceptions(form, pos);
+        if !exceptions.is_empty() {
+            let mut candidates = vec![form.to_string()];
+            candidates.extend(exceptions.iter().cloned());
+            return self.known(candidates, pos);
+        }
+
+        let mut forms = self.detach(&[form.to_string()], pos);
+        let mut candidates = vec![form.to_string()];
+        candidates.extend(forms.iter().cloned());
+        let known = self.known(candidates.clone(), pos);
+        if !known.is_empty() {
+            return known;
+        }
+
+        // rules never lengthen a form, so there are finitely many forms and this stops once no new one is produced
+        let mut seen: HashSet<String> = candidates.into_iter().collect();
+        while !forms.is_empty() {
+            forms = self.detach(&forms, pos).into_iter().filter(|form| seen.insert(form.clone())).collect();
+            let known = self.known(forms.clone(), pos);
+            if !known.is_empty() {
+                return known;
+            }
+        }
+        Vec::new()
+    }
+
+    /// The shortest lemma of the word, or the word itself if none is found
+    pub fn lemmatize(&self, word: &str, pos: Pos) -> String {
+        self.morphy(word, pos)
+            .into_iter()
+            .min_by_key(|lemma| lemma.chars().count())
+            .unwrap_or_else(|| word.to_string())
+    }
+
+    fn detach(&self, forms: &[String], pos: Pos) -> Vec<String> {
+        let substitutions = match self.substitutions.get(&pos) {
+            Some(substitutions) => substitutions,
+            None => return Vec::new(),
+        };
+        forms.iter()
+            .flat_map(|form| substitutions.iter()
+                .filter(move |(suffix, _)| form.ends_with(suffix.as_str()))
+                .map(move |(suffix, replacement)| format!("{}{}", &form[..form.len() - suffix.len()], replacement)))
+            .collect()
+    }
+
+    /// the candidates that are in the lexicon, without duplicates and in order
+    fn known(&self, candidates: Vec<String>, pos: Pos) -> Vec<String> {
+        let mut known = Vec::new();
+        for candidate in candidates {
+            if self.lexicon.contains(&candidate, pos) && !known.contains(&candidate) {
+                known.push(candidate);
+            }
+        }
+        known
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lexicon() -> WordNetLexicon {
+        let mut lexicon = WordNetLexicon::new();
+        lexicon.add_index("  1 This software and database is being provided\nabaca n 1 1 @ 1 0 12158031\ndog n 7 5 @ ~ #m #p %p 7 1 02084071\nwoman n 4 5 @ ~ #m %p + 4 3 10787470\n", Pos::Noun);
+        lexicon.add_index("hope\nrun\nbe\n", Pos::Verb);
+        lexicon.add_index("good\nbig\nlate\n", Pos::Adjective);
+        lexicon.add_exceptions("better good well\nbigger big\n", Pos::Adjective);
+        lexicon.add_exceptions("ran run\nwas be\n", Pos::Verb);
+        lexicon
+    }
+
+    #[test]
+    fn test_lemmatize() {
+        let lemmatizer = Lemmatizer::new(lexicon());
+        assert_eq!(lemmatizer.lemmatize("dogs", Pos::Noun), "dog");
+        assert_eq!(lemmatizer.lemmatize("women", Pos::Noun), "woman");
+        assert_eq!(lemmatizer.lemmatize("hoping", Pos::Verb), "hope");
+        assert_eq!(lemmatizer.lemmatize("hopped", Pos::Verb), "hopped");
+        assert_eq!(lemmatizer.lemmatize("ran", Pos::Verb), "run");
+        assert_eq!(lemmatizer.lemmatize("better", Pos::Adjective), "good");
+        assert_eq!(lemmatizer.lemmatize("later", Pos::Adjective), "late");
+    }
+
+    #[test]
+    fn test_morphy_returns_all_known_lemmas() {
+        let lemmatizer = Lemmatizer::new(lexicon());
+        assert_eq!(lemmatizer.morphy("hoped", Pos::Verb), vec!["hope"]);
+        assert_eq!(lemmatizer.morphy("runs", Pos::Verb), vec!["run"]);
+        assert!(lemmatizer.morphy("dogs", Pos::Verb).is_empty());
+    }
+
+    #[test]
+    fn test_custom_rules_terminate() {
+        let mut lemmatizer = Lemmatizer::new(lexicon());
+        assert!(lemmatizer.set_substitutions(Pos::Noun, &[("e", "es")]).is_err());
+        lemmatizer.set_substitutions(Pos::Noun, &[("a", "a"), ("ab", "ba"), ("ba", "ab")]).unwrap();
+        assert!(lemmatizer.morphy("abab", Pos::Noun).is_empty());
+        assert_eq!(lemmatizer.lemmatize("xyza", Pos::Noun), "xyza");
+    }
+
+    #[test]
+    fn test_header_lines_are_skipped() {
+        let lexicon = lexicon();
+        assert!(!lexicon.contains("1", Pos::Noun));
+        assert!(lexicon.contains("abaca", Pos::Noun));
+    }
+
+    #[test]
+    fn test_from_penn_tag() {
+        assert_eq!(Pos::from_penn_tag("VBD"), Some(Pos::Verb));
+        assert_eq!(Pos::from_penn_tag("DT"), None);
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use crate::metrics::confusion::ConfusionMatrix;
+use crate::tag::{untag, TaggedSentence, Tagger};
+
+/// Quality of a tagger, measured against gold standard tagged sentences
+#[derive(Clone, Debug)]
+pub struct Evaluation {
+    /// fraction of the tokens that got the gold tag
+    pub accuracy: f64,
+    /// gold tags versus predicted tags
+    pub confusion: ConfusionMatrix,
+    /// precision and recall for every tag in gold or predicted. None where undefined (the tag was never predicted, or never in gold)
+    pub per_tag: BTreeMap<String, TagScore>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TagScore {
+    pub precision: Option<f64>,
+    pub recall: Option<f64>,
+}
+
+/// Tags the tokens of every gold sentence, and compares the result to the gold tags
+pub fn evaluate<T: Tagger + ?Sized>(tagger: &T, gold: &[TaggedSentence]) -> Evaluation {
+    let mut reference = Vec::new();
+    let mut predicted = Vec::new();
+    for sentence in gold {
+        let tagged = tagger.tag(&untag(sentence));
+        reference.extend(sentence.iter().map(|(_, tag)| *tag));
+        predicted.extend(tagged.into_iter().map(|(_, tag)| tag));
+    }
+
+    let confusion = ConfusionMatrix::new(&reference, &predicted);
+    let per_tag = confusion.labels().iter()
+        .map(|tag| (tag.clone(), TagScore { precision: confusion.precision(tag), recall: confusion.recall(tag) }))
+        .collect();
+    Evaluation { accuracy: confusion.accuracy(), confusion, per_tag }
+}
+
+/// k-fold cross-validation: splits the corpus into k consecutive folds, and for every fold trains a tagger
+/// on the other folds and evaluates it on this one. Returns the evaluation of every fold.
+///
+/// ```
+/// use rltk::tag::evaluation::cross_validate;
+/// use rltk::tag::sequential::UnigramTagger;
+///
+/// let corpus = vec![vec![("the", "DT"), ("dog", "NN")], vec![("the", "DT"), ("cat", "NN")],
+///                   vec![("a", "DT"), ("dog", "NN")], vec![("a", "DT"), ("cat", "NN")]];
+/// let folds = cross_validate(&corpus, 2, |train| UnigramTagger::train(train, None, 0));
+/// let mean_accuracy = folds.iter().map(|fold| fold.accuracy).sum::<f64>() / folds.len() as f64;
+/// assert_eq!(mean_accuracy, 0.5);
+/// ```
+/// # Panics
+/// if k is 0 or larger than the number of sentences
+pub fn cross_validate<'a, T, F>(corpus: &[TaggedSentence<'a>], k: usize, train: F) -> Vec<Evaluation>
+    where T: Tagger, F: Fn(&[TaggedSentence<'a>]) -> T {
+    assert!(k > 0 && k <= corpus.len(), "k must be > 0 and at most the number of sentences");
+    (0..k).map(|fold| {
+        let (start, end) = (fold * corpus.len() / k, (fold + 1) * corpus.len() / k);
+        let training: Vec<TaggedSentence> = corpus[..start].iter().chain(corpus[end..].iter()).cloned().collect();
+        let tagger = train(&training);
+        evaluate(&tagger, &corpus[start..end])
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::sequential::DefaultTagger;
+
+    #[test]
+    fn test_evaluate() {
+        let gold = vec![vec![("the", "DT"), ("dog", "NN")], vec![("barks", "VBZ")]];
+        let evaluation = DefaultTagger::new("NN").evaluate(&gold);
+        assert_eq!(evaluation.accuracy, 1.0 / 3.0);
+        assert_eq!(evaluation.confusion.get("DT", "NN"), 1);
+        assert_eq!(evaluation.per_tag["NN"], TagScore { precision: Some(1.0 / 3.0), recall: Some(1.0) });
+        assert_eq!(evaluation.per_tag["DT"], TagScore { precision: None, recall: Some(0.0) });
+    }
+
+    #[test]
+    fn test_cross_validate_folds() {
+        let corpus = vec![vec![("a", "X")], vec![("b", "Y")], vec![("c", "Z")]];
+        let folds = cross_validate(&corpus, 3, |train| {
+            assert_eq!(train.len(), 2);
+            DefaultTagger::new("X")
+        });
+        let accuracies: Vec<f64> = folds.iter().map(|fold| fold.accuracy).collect();
+        assert_eq!(accuracies, vec![1.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+
+use crate::lm::probability::{ConditionalFreqDist, FreqDist};
+use crate::tag::{TaggedSentence, Tagger};
+
+/// Supervised first order Hidden Markov Model tagger: the tags are the hidden states, the tokens the observed symbols.
+/// Prior, transition and emission probabilities are Lidstone estimates from a tagged corpus.
+/// All probabilities are computed in log space, to avoid underflow on long sentences.
+///
+/// ```
+/// use rltk::tag::Tagger;
+/// use rltk::tag::hmm::HiddenMarkovModelTagger;
+///
+/// let corpus = vec![vec![("the", "DT"), ("dog", "NN"), ("barks", "VBZ")],
+///                   vec![("the", "DT"), ("cat", "NN"), ("sleeps", "VBZ")]];
+/// let tagger = HiddenMarkovModelTagger::train(&corpus, 0.1).unwrap();
+/// assert_eq!(tagger.best_path(&["the", "dog", "sleeps"]), vec!["DT", "NN", "VBZ"]);
+/// ```
+pub struct HiddenMarkovModelTagger {
+    states: Vec<String>,
+    symbols: HashSet<String>,
+    priors: FreqDist<String>,
+    transitions: ConditionalFreqDist<String, String>,
+    emissions: ConditionalFreqDist<String, String>,
+    gamma: f64,
+}
+
+impl HiddenMarkovModelTagger {
+    /// Estimates the model from a tagged corpus. Gamma is the Lidstone smoothing parameter
+    /// that is added to every count, so that unseen transitions and words get a small probability (0.1 is a reasonable default).
+    /// Fails for a corpus without tokens and a gamma that is not positive and finite.
+    pub fn train(corpus: &[TaggedSentence], gamma: f64) -> anyhow::Result<Self> {
+        if !gamma.is_finite() || gamma <= 0.0 {
+            return Err(anyhow!("smoothing must be positive, not {}", gamma));
+        }
+        let mut states = HashSet::new();
+        let mut symbols = HashSet::new();
+        let mut priors = FreqDist::new();
+        let mut transitions = ConditionalFreqDist::new();
+        let mut emissions = ConditionalFreqDist::new();
+
+        for sentence in corpus {
+            if let Some((_, first)) = sentence.first() {
+                priors.increment(first.to_string());
+            }
+            for (token, tag) in sentence {
+                states.insert(tag.to_string());
+                symbols.insert(token.to_string());
+                emissions.increment(tag.to_string(), token.to_string());
+            }
+            for pair in sentence.windows(2) {
+                transitions.increment(pair[0].1.to_string(), pair[1].1.to_string());
+            }
+        }
+
+        if states.is_empty() {
+            return Err(anyhow!("empty training corpus"));
+        }
+        let mut states: Vec<String> = states.into_iter().collect();
+        states.sort();
+        Ok(Self { states, symbols, priors, transitions, emissions, gamma })
+    }
+
+    /// the tags, in the order used by `posteriors`
+    pub fn states(&self) -> &[String] {
+        &self.states
+    }
+
+    fn log_prior(&self, state: &str) -> f64 {
+        self.priors.lidstone(&state.to_string(), self.gamma, self.states.len()).ln()
+    }
+
+    fn log_transition(&self, from: &str, to: &str) -> f64 {
+        self.transitions.lidstone(&from.to_string(), &to.to_string(), self.gamma, self.states.len()).ln()
+    }
+
+    /// one extra bin for all unknown symbols
+    fn log_emission(&self, state: &str, symbol: &str) -> f64 {
+        self.emissions.lidstone(&state.to_string(), &symbol.to_string(), self.gamma, self.symbols.len() + 1).ln()
+    }
+
+    /// The most probable sequence of tags for the tokens (Viterbi decoding)
+    pub fn best_path(&self, tokens: &[&str]) -> Vec<String> {
+        if tokens.is_empty() {
+            return Vec::new();
+        }
+        let k = self.states.len();
+        let mut scores: Vec<f64> = self.states.iter()
+            .map(|state| self.log_prior(state) + self.log_emission(state, tokens[0]))
+            .collect();
+        let mut backpointers: Vec<Vec<usize>> = Vec::with_capacity(tokens.len());
+
+        for token in &tokens[1..] {
+            let mut next_scores = Vec::with_capacity(k);
+            let mut pointers = Vec::with_capacity(k);
+            for to in &self.states {
+                let (best, score) = self.states.iter().enumerate()
+                    .map(|(i, from)| (i, scores[i] + self.log_transition(from, to)))
+                    .max_by(|(_, left), (_, right)| left.total_cmp(right))
+                    .unwrap();
+                next_scores.push(score + self.log_emission(to, token));
+                pointers.push(best);
+            }
+            scores = next_scores;
+            backpointers.push(pointers);
+        }
+
+        let mut best = argmax(&scores);
+        let mut path = vec![best];
+        for pointers in backpointers.iter().rev() {
+            best = pointers[best];
+            path.push(best);
+        }
+        path.into_iter().rev().map(|i| self.states[i].clone()).collect()
+    }
+
+    /// log P(tag_t = state, tokens[..=t]) for every t and state
+    pub fn forward(&self, tokens: &[&str]) -> Vec<Vec<f64>> {
+        let mut alpha: Vec<Vec<f64>> = Vec::with_capacity(tokens.len());
+        for (t, token) in tokens.iter().enumerate() {
+            let row = self.states.iter().map(|to| {
+                let incoming = if t == 0 {
+                    self.log_prior(to)
+                } else {
+                    log_sum_exp(self.states.iter().enumerate().map(|(i, from)| alpha[t - 1][i] + self.log_transition(from, to)))
+                };
+                incoming + self.log_emission(to, token)
+            }).collect();
+            alpha.push(row);
+        }
+        alpha
+    }
+
+    /// log P(tokens[t+1..] | tag_t = state) for every t and state
+    pub fn backward(&self, tokens: &[&str]) -> Vec<Vec<f64>> {
+        let n = tokens.len();
+        let mut beta = vec![vec![0.0; self.states.len()]; n];
+        for t in (0..n.saturating_sub(1)).rev() {
+            for (i, from) in self.states.iter().enumerate() {
+                beta[t][i] = log_sum_exp(self.states.iter().enumerate()
+                    .map(|(j, to)| self.log_transition(from, to) + self.log_emission(to, tokens[t + 1]) + beta[t + 1][j]));
+            }
+        }
+        beta
+    }
+
+    /// log P(tokens), summed over all possible tag sequences
+    pub fn log_probability(&self, tokens: &[&str]) -> f64 {
+        match self.forward(tokens).last() {
+            Some(last) => log_sum_exp(last.iter().copied()),
+            None => 0.0,
+        }
+    }
+
+    /// P(tag_t = state | tokens) for every t, with the states in the order of `states()` (forward-backward)
+    pub fn posteriors(&self, tokens: &[&str]) -> Vec<Vec<f64>> {
+        let alpha = self.forward(tokens);
+        let beta = self.backward(tokens);
+        let total = match alpha.last() {
+            Some(last) => log_sum_exp(last.iter().copied()),
+            None => return Vec::new(),
+        };
+        alpha.iter().zip(beta.iter())
+            .map(|(a, b)| a.iter().zip(b.iter()).map(|(a, b)| (a + b - total).exp()).collect())
+            .collect()
+    }
+}
+
+impl Tagger for HiddenMarkovModelTagger {
+    fn tag<'a>(&self, tokens: &[&'a str]) -> Vec<(&'a str, String)> {
+        tokens.iter().copied().zip(self.best_path(tokens)).collect()
+    }
+}
+
+fn argmax(values: &[f64]) -> usize {
+    values.iter().enumerate()
+        .max_by(|(_, left), (_, right)| left.total_cmp(right))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// log(sum(exp(x))), without overflow
+fn log_sum_exp(values: impl Iterator<Item=f64>) -> f64 {
+    let values: Vec<f64> = values.collect();
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + values.iter().map(|v| (v - max).exp()).sum::<f64>().ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagger() -> HiddenMarkovModelTagger {
+        let corpus = vec![
+            vec![("I", "PRP"), ("can", "MD"), ("fish", "VB")],
+            vec![("a", "DT"), ("can", "NN"), ("of", "IN"), ("fish", "NN")],
+            vec![("they", "PRP"), ("can", "MD"), ("swim", "VB")],
+            vec![("a", "DT"), ("fish", "NN"), ("can", "MD"), ("swim", "VB")],
+        ];
+        HiddenMarkovModelTagger::train(&corpus, 0.1).unwrap()
+    }
+
+    /// every possible tag sequence, with its joint log probability
+    fn enumerate(tagger: &HiddenMarkovModelTagger, tokens: &[&str]) -> Vec<(Vec<usize>, f64)> {
+        let k = tagger.states().len();
+        let mut paths: Vec<(Vec<usize>, f64)> = vec![(Vec::new(), 0.0)];
+        for (t, token) in tokens.iter().enumerate() {
+            let mut next = Vec::new();
+            for (path, score) in &paths {
+                for s in 0..k {
+                    let state = &tagger.states()[s];
+                    let step = if t == 0 {
+                        tagger.log_prior(state)
+                    } else {
+                        tagger.log_transition(&tagger.states()[*path.last().unwrap()], state)
+                    };
+                    let mut extended: Vec<usize> = path.clone();
+                    extended.push(s);
+                    next.push((extended, score + step + tagger.log_emission(state, token)));
+                }
+            }
+            paths = next;
+        }
+        paths
+    }
+
+    #[test]
+    fn test_tag() {
+        let tagger = tagger();
+        assert_eq!(tagger.best_path(&["a", "can", "of", "fish"]), vec!["DT", "NN", "IN", "NN"]);
+        assert_eq!(tagger.best_path(&["they", "can", "fish"]), vec!["PRP", "MD", "VB"]);
+        assert!(tagger.best_path(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_viterbi_finds_most_probable_path() {
+        let tagger = tagger();
+        let tokens = ["fish", "can", "swim"];
+        let (best, _) = enumerate(&tagger, &tokens).into_iter()
+            .max_by(|(_, left), (_, right)| left.total_cmp(right))
+            .unwrap();
+        let best: Vec<String> = best.into_iter().map(|s| tagger.states()[s].clone()).collect();
+        assert_eq!(tagger.best_path(&tokens), best);
+    }
+
+    #[test]
+    fn test_forward_sums_over_all_paths() {
+        let tagger = tagger();
+        let tokens = ["a", "fish", "unknown"];
+        let expected = log_sum_exp(enumerate(&tagger, &tokens).into_iter().map(|(_, score)| score));
+        assert!((tagger.log_probability(&tokens) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_posteriors_are_distributions() {
+        let tagger = tagger();
+        let posteriors = tagger.posteriors(&["I", "can", "swim"]);
+        assert_eq!(posteriors.len(), 3);
+        for distribution in posteriors {
+            assert!((distribution.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_invalid_training() {
+        let corpus = vec![vec![("a", "DT")]];
+        assert!(HiddenMarkovModelTagger::train(&corpus, 0.0).is_err());
+        assert!(HiddenMarkovModelTagger::train(&corpus, -1.0).is_err());
+        assert!(HiddenMarkovModelTagger::train(&corpus, f64::NAN).is_err());
+        assert!(HiddenMarkovModelTagger::train(&corpus, f64::INFINITY).is_err());
+        assert!(HiddenMarkovModelTagger::train(&[], 0.1).is_err());
+        assert!(HiddenMarkovModelTagger::train(&[vec![]], 0.1).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+pub mod evaluation;
+pub mod hmm;
+pub mod perceptron;
+pub mod sequential;
+
+/// A sentence in which every token has been tagged: (token, tag) pairs
+pub type TaggedSentence<'a> = Vec<(&'a str, &'a str)>;
+
+/// A tagger assigns a tag, like a part of speech, to every token in a sentence
+pub trait Tagger {
+    /// Returns (token, tag) pairs, in the order of the tokens
+    fn tag<'a>(&self, tokens: &[&'a str]) -> Vec<(&'a str, String)>;
+
+    /// Tags a sentence, given as an iterator of tokens
+    fn tag_sentence<'a>(&self, sentence: impl Iterator<Item=&'a &'a str>) -> Vec<(&'a str, String)> where Self: Sized {
+        let tokens: Vec<&str> = sentence.copied().collect();
+        self.tag(&tokens)
+    }
+
+    /// Compares the tags for the tokens in the gold sentences with their gold tags
+    fn evaluate(&self, gold: &[TaggedSentence]) -> evaluation::Evaluation {
+        evaluation::evaluate(self, gold)
+    }
+}
+
+/// the tokens of a tagged sentence
+pub fn untag<'a>(sentence: &[(&'a str, &'a str)]) -> Vec<&'a str> {
+    sentence.iter().map(|(token, _)| *token).collect()
+}
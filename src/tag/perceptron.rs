@@ -0,0 +1,401 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+
+use anyhow::anyhow;
+
+use crate::tag::{TaggedSentence, Tagger};
+use crate::util::random::XorShift;
+
+const START: [&str; 2] = ["-START-", "-START2-"];
+const END: [&str; 2] = ["-END-", "-END2-"];
+
+/// Words that occur at least this often in the training data, are candidates for the tag dictionary
+const FREQUENCY_THRESHOLD: usize = 20;
+/// ... if they get the same tag in this fraction of the occurrences
+const AMBIGUITY_THRESHOLD: f64 = 0.97;
+
+/// Part-of-speech tagger using an averaged perceptron, as in nltk's default `pos_tag`.
+/// Frequent and unambiguous words are tagged from a dictionary, all others are predicted
+/// from features of the word, its neighbours and the previous two tags.
+///
+/// ```
+/// use rltk::tag::Tagger;
+/// use rltk::tag::perceptron::PerceptronTagger;
+///
+/// let sentences = vec![vec![("the", "DT"), ("dog", "NN"), ("barks", "VBZ")],
+///                      vec![("a", "DT"), ("cat", "NN"), ("sleeps", "VBZ")]];
+/// let mut tagger = PerceptronTagger::new();
+/// tagger.train(&sentences, 5);
+///
+/// let tagged = tagger.tag(&["the", "cat", "barks"]);
+/// assert_eq!(tagged[1], ("cat", "NN".to_string()));
+/// ```
+pub struct PerceptronTagger {
+    model: AveragedPerceptron,
+    tagdict: HashMap<String, String>,
+    seed: u64,
+}
+
+impl PerceptronTagger {
+    /// An untrained tagger
+    pub fn new() -> Self {
+        Self {
+            model: AveragedPerceptron::new(),
+            tagdict: HashMap::new(),
+            seed: 1,
+        }
+    }
+
+    /// seed for shuffling the sentences between training iterations
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Trains the model on tagged sentences, in the given number of iterations (5 is a good default)
+    pub fn train(&mut self, sentences: &[TaggedSentence], iterations: usize) {
+        self.make_tagdict(sentences);
+        let mut sentences: Vec<&TaggedSentence> = sentences.iter().collect();
+        let mut random = XorShift::new(self.seed);
+
+        for _ in 0..iterations {
+            for sentence in &sentences {
+                let words: Vec<&str> = sentence.iter().map(|(word, _)| *word).collect();
+                let context = context(&words);
+                let (mut prev, mut prev2) = (START[0].to_string(), START[1].to_string());
+                for (i, (word, tag)) in sentence.iter().enumerate() {
+                    let guess = match self.tagdict.get(*word) {
+                        Some(tag) => tag.clone(),
+                        None => {
+                            let features = features(i, word, &context, &prev, &prev2);
+                            let guess = self.model.predict(&features);
+                            self.model.update(tag, &guess, &features);
+                            guess
+                        }
+                    };
+                    prev2 = prev;
+                    prev = guess;
+                }
+            }
+            random.shuffle(&mut sentences);
+        }
+        self.model.average_weights();
+    }
+
+    /// Saves the model to a file, in a plain text format
+    pub fn save(&self, filename: &str) -> anyhow::Result<()> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads a model that was saved with `save`
+    pub fn load(filename: &str) -> anyhow::Result<Self> {
+        let file = File::open(filename)?;
+        Self::read(io::BufReader::new(file))
+    }
+
+    /// Writes the model as tab separated lines:
+    /// `class <tag>`, `tagdict <word> <tag>` and `weight <feature> <tag> <value>`
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        for class in &self.model.classes {
+            writeln!(writer, "class\t{}", class)?;
+        }
+        for (word, tag) in &self.tagdict {
+            writeln!(writer, "tagdict\t{}\t{}", word, tag)?;
+        }
+        for (feature, weights) in &self.model.weights {
+            for (class, weight) in weights {
+                writeln!(writer, "weight\t{}\t{}\t{}", feature, class, weight)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a model written by `write`
+    pub fn read(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut tagger = Self::new();
+        for line in reader.lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                ["class", class] => {
+                    tagger.model.classes.insert(class.to_string());
+                }
+                ["tagdict", word, tag] => {
+                    tagger.tagdict.insert(word.to_string(), tag.to_string());
+                }
+                ["weight", feature, class, weight] => {
+                    tagger.model.weights.entry(feature.to_string()).or_default().insert(class.to_string(), weight.parse()?);
+                }
+                [""] => {}
+                _ => return Err(anyhow!("invalid line in perceptron model: {}", line)),
+            }
+        }
+        Ok(tagger)
+    }
+
+    fn make_tagdict(&mut self, sentences: &[TaggedSentence]) {
+        let mut counts: HashMap<&str, HashMap<&str, usize>> = HashMap::new();
+        for sentence in sentences {
+            for (word, tag) in sentence {
+                *counts.entry(word).or_default().entry(tag).or_insert(0) += 1;
+                self.model.classes.insert(tag.to_string());
+            }
+        }
+        for (word, tag_counts) in counts {
+            let n: usize = tag_counts.values().sum();
+            if let Some((tag, mode)) = tag_counts.into_iter().max_by_key(|(tag, count)| (*count, *tag)) {
+                if n >= FREQUENCY_THRESHOLD && mode as f64 / n as f64 >= AMBIGUITY_THRESHOLD {
+                    self.tagdict.insert(word.to_string(), tag.to_string());
+                }
+            }
+        }
+    }
+}
+
+impl Default for PerceptronTagger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tagger for PerceptronTagger {
+    fn tag<'a>(&self, tokens: &[&'a str]) -> Vec<(&'a str, String)> {
+        let context = context(tokens);
+        let (mut prev, mut prev2) = (START[0].to_string(), START[1].to_string());
+        let mut tagged = Vec::with_capacity(tokens.len());
+        for (i, word) in tokens.iter().enumerate() {
+            let tag = match self.tagdict.get(*word) {
+                Some(tag) => tag.clone(),
+                None => self.model.predict(&features(i, word, &context, &prev, &prev2)),
+            };
+            prev2 = prev;
+            prev = tag.clone();
+            tagged.push((*word, tag));
+        }
+        tagged
+    }
+}
+
+/// the normalized words of the sentence, surrounded by start and end markers
+fn context(words: &[&str]) -> Vec<String> {
+    START.iter().map(|s| s.to_string())
+        .chain(words.iter().map(|word| normalize(word)))
+        .chain(END.iter().map(|s| s.to_string()))
+        .collect()
+}
+
+fn normalize(word: &str) -> String {
+    if word.contains('-') && !word.starts_with('-') {
+        "!HYPHEN".to_string()
+    } else if word.chars().count() == 4 && word.chars().all(|c| c.is_ascii_digit()) {
+        "!YEAR".to_string()
+    } else if word.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        "!DIGITS".to_string()
+    } else {
+        word.to_lowercase()
+    }
+}
+
+/// the last (at most) 3 chars
+fn suffix(word: &str) -> String {
+    let chars: Vec<char> = word.chars().collect();
+    chars[chars.len().saturating_sub(3)..].iter().collect()
+}
+
+fn features(i: usize, word: &str, context: &[String], prev: &str, prev2: &str) -> HashMap<String, f64> {
+    let i = i + START.len();
+    let first: String = word.chars().take(1).collect();
+    let names = [
+        "bias".to_string(),
+        format!("i suffix {}", suffix(word)),
+        format!("i pref1 {}", first),
+        format!("i-1 tag {}", prev),
+        format!("i-2 tag {}", prev2),
+        format!("i tag+i-2 tag {} {}", prev, prev2),
+        format!("i word {}", context[i]),
+        format!("i-1 tag+i word {} {}", prev, context[i]),
+        format!("i-1 word {}", context[i - 1]),
+        format!("i-1 suffix {}", suffix(&context[i - 1])),
+        format!("i-2 word {}", context[i - 2]),
+        format!("i+1 word {}", context[i + 1]),
+        format!("i+1 suffix {}", suffix(&context[i + 1])),
+        format!("i+2 word {}", context[i + 2]),
+    ];
+    let mut features = HashMap::new();
+    for name in names {
+        *features.entry(name).or_insert(0.0) += 1.0;
+    }
+    features
+}
+
+/// Multiclass perceptron that keeps track of the accumulated weights, so that they can be averaged after training
+struct AveragedPerceptron {
+    weights: HashMap<String, HashMap<String, f64>>,
+    classes: BTreeSet<String>,
+    // accumulated weight per (feature, class), up to the timestamp
+    totals: HashMap<(String, String), f64>,
+    // the instance count at which the weight of (feature, class) last changed
+    timestamps: HashMap<(String, String), usize>,
+    instances: usize,
+}
+
+impl AveragedPerceptron {
+    fn new() -> Self {
+        Self {
+            weights: HashMap::new(),
+            classes: BTreeSet::new(),
+            totals: HashMap::new(),
+            timestamps: HashMap::new(),
+            instances: 0,
+        }
+    }
+
+    /// the class with the highest score; ties are broken by the class name, for stability
+    fn predict(&self, features: &HashMap<String, f64>) -> String {
+        let mut scores: HashMap<&str, f64> = HashMap::new();
+        for (feature, value) in features {
+            if let Some(weights) = self.weights.get(feature) {
+                for (class, weight) in weights {
+                    *scores.entry(class).or_insert(0.0) += value * weight;
+                }
+            }
+        }
+        self.classes.iter()
+            .map(|class| (scores.get(class.as_str()).copied().unwrap_or(0.0), class))
+            .max_by(|(left_score, left), (right_score, right)| left_score.total_cmp(right_score).then(left.cmp(right)))
+            .map(|(_, class)| class.clone())
+            .unwrap_or_default()
+    }
+
+    fn update(&mut self, truth: &str, guess: &str, features: &HashMap<String, f64>) {
+        self.instances += 1;
+        if truth == guess {
+            return;
+        }
+        for feature in features.keys() {
+            self.update_feature(truth, feature, 1.0);
+            self.update_feature(guess, feature, -1.0);
+        }
+    }
+
+    fn update_feature(&mut self, class: &str, feature: &str, delta: f64) {
+        let weight = self.weights.entry(feature.to_string()).or_default().entry(class.to_string()).or_insert(0.0);
+        let key = (feature.to_string(), class.to_string());
+        let timestamp = self.timestamps.entry(key.clone()).or_insert(0);
+        *self.totals.entry(key).or_insert(0.0) += (self.instances - *timestamp) as f64 * *weight;
+        *timestamp = self.instances;
+        *weight += delta;
+    }
+
+    /// Replaces every weight by its average over the training instances since the last averaging,
+    /// and resets the averaging, so that training can continue from the averaged weights
+    fn average_weights(&mut self) {
+        if self.instances == 0 {
+            return;
+        }
+        for (feature, weights) in self.weights.iter_mut() {
+            let mut averaged_weights = HashMap::new();
+            for (class, weight) in weights.iter() {
+                let key = (feature.clone(), class.clone());
+                let total = self.totals.get(&key).copied().unwrap_or(0.0)
+                    + (self.instances - self.timestamps.get(&key).copied().unwrap_or(0)) as f64 * weight;
+                let averaged = (total / self.instances as f64 * 1000.0).round() / 1000.0;
+                if averaged != 0.0 {
+                    averaged_weights.insert(class.clone(), averaged);
+                }
+            }
+            *weights = averaged_weights;
+        }
+        self.totals.clear();
+        self.timestamps.clear();
+        self.instances = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn training_data() -> Vec<TaggedSentence<'static>> {
+        vec![
+            vec![("the", "DT"), ("dog", "NN"), ("barks", "VBZ"), (".", ".")],
+            vec![("a", "DT"), ("cat", "NN"), ("sleeps", "VBZ"), (".", ".")],
+            vec![("the", "DT"), ("old", "JJ"), ("man", "NN"), ("walks", "VBZ"), ("home", "NN"), (".", ".")],
+            vec![("dogs", "NNS"), ("bark", "VBP"), ("at", "IN"), ("the", "DT"), ("moon", "NN"), (".", ".")],
+        ]
+    }
+
+    #[test]
+    fn test_tags_training_data() {
+        let sentences = training_data();
+        let mut tagger = PerceptronTagger::new();
+        tagger.train(&sentences, 10);
+
+        for sentence in &sentences {
+            let words: Vec<&str> = sentence.iter().map(|(word, _)| *word).collect();
+            let tagged = tagger.tag(&words);
+            let tags: Vec<&str> = tagged.iter().map(|(_, tag)| tag.as_str()).collect();
+            let expected: Vec<&str> = sentence.iter().map(|(_, tag)| *tag).collect();
+            assert_eq!(tags, expected);
+        }
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let mut tagger = PerceptronTagger::new();
+        tagger.train(&training_data(), 5);
+
+        let mut buffer = Vec::new();
+        tagger.write(&mut buffer).unwrap();
+        let loaded = PerceptronTagger::read(buffer.as_slice()).unwrap();
+
+        let sentence = ["a", "old", "dog", "walks", "."];
+        assert_eq!(tagger.tag(&sentence), loaded.tag(&sentence));
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        assert!(PerceptronTagger::read("weight\tbias".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("well-known"), "!HYPHEN");
+        assert_eq!(normalize("1984"), "!YEAR");
+        assert_eq!(normalize("42nd"), "!DIGITS");
+        assert_eq!(normalize("The"), "the");
+    }
+
+    #[test]
+    fn test_averaging_restarts_after_training() {
+        let features = HashMap::from([("f".to_string(), 1.0)]);
+        let mut model = AveragedPerceptron::new();
+        // a weight counts from the instance after its update: A is 0, then 1
+        model.update("A", "B", &features);
+        model.update("A", "A", &features);
+        model.average_weights();
+        assert_eq!(model.weights["f"]["A"], 0.5);
+        assert_eq!(model.instances, 0);
+
+        // A is 0.5 for two new instances, then -0.5: averaged over these three instances only
+        model.update("A", "A", &features);
+        model.update("B", "A", &features);
+        model.update("A", "A", &features);
+        model.average_weights();
+        assert_eq!(model.weights["f"]["A"], 0.167);
+    }
+
+    #[test]
+    fn test_tag_sentence() {
+        let mut tagger = PerceptronTagger::new();
+        tagger.train(&training_data(), 5);
+        let sentence = ["the", "dog", "barks"];
+        let tagged = tagger.tag_sentence(sentence.iter());
+        assert_eq!(tagged.len(), 3);
+        assert_eq!(tagged[0], ("the", "DT".to_string()));
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+
+use crate::tag::{TaggedSentence, Tagger};
+use crate::util::{ngrams, pad_sequence_left};
+
+/// tag history before the start of a sentence
+const START: &str = "<s>";
+
+/// A tagger that tags one token at a time, from left to right, and that can defer to a backoff tagger
+/// for tokens it cannot tag itself. Chains of backoff taggers are built from the most specific to the most general:
+///
+/// ```
+/// use rltk::tag::Tagger;
+/// use rltk::tag::sequential::{BigramTagger, DefaultTagger, UnigramTagger};
+///
+/// let corpus = vec![vec![("the", "DT"), ("dog", "NN"), ("barks", "VBZ")],
+///                   vec![("the", "DT"), ("barks", "NNS"), ("stop", "VBP")]];
+/// let default = DefaultTagger::new("NN");
+/// let unigram = UnigramTagger::train(&corpus, Some(Box::new(default)), 0);
+/// let bigram = BigramTagger::train(&corpus, Some(Box::new(unigram)), 0);
+///
+/// let tagged = bigram.tag(&["the", "cat", "barks"]);
+/// assert_eq!(tagged, vec![("the", "DT".to_string()), ("cat", "NN".to_string()), ("barks", "VBZ".to_string())]);
+/// ```
+/// Tokens that no tagger in the chain can tag, get an empty tag. End the chain with a `DefaultTagger` to prevent that.
+pub trait SequentialTagger {
+    /// The tag for the token at index, given the tags of the tokens before it. None if this tagger cannot decide.
+    fn choose_tag(&self, tokens: &[&str], index: usize, history: &[String]) -> Option<String>;
+
+    fn backoff(&self) -> Option<&dyn SequentialTagger>;
+
+    /// The tag for the token at index, from this tagger or else from its backoff chain
+    fn tag_one(&self, tokens: &[&str], index: usize, history: &[String]) -> Option<String> {
+        self.choose_tag(tokens, index, history)
+            .or_else(|| self.backoff().and_then(|backoff| backoff.tag_one(tokens, index, history)))
+    }
+}
+
+impl<T: SequentialTagger> Tagger for T {
+    fn tag<'a>(&self, tokens: &[&'a str]) -> Vec<(&'a str, String)> {
+        let mut history = Vec::with_capacity(tokens.len());
+        for index in 0..tokens.len() {
+            let tag = self.tag_one(tokens, index, &history).unwrap_or_default();
+            history.push(tag);
+        }
+        tokens.iter().copied().zip(history).collect()
+    }
+}
+
+/// Assigns the same tag to every token
+pub struct DefaultTagger {
+    tag: String,
+}
+
+impl DefaultTagger {
+    pub fn new(tag: &str) -> Self {
+        Self { tag: tag.to_string() }
+    }
+}
+
+impl SequentialTagger for DefaultTagger {
+    fn choose_tag(&self, _tokens: &[&str], _index: usize, _history: &[String]) -> Option<String> {
+        Some(self.tag.clone())
+    }
+
+    fn backoff(&self) -> Option<&dyn SequentialTagger> {
+        None
+    }
+}
+
+/// Assigns the tag of the first pattern that matches the token. Patterns are anchored at the start of the token.
+///
+/// ```
+/// use rltk::tag::Tagger;
+/// use rltk::tag::sequential::RegexpTagger;
+///
+/// let tagger = RegexpTagger::new(&[(r"^-?[0-9]+(\.[0-9]+)?$", "CD"), (r".*ing$", "VBG"), (r".*", "NN")], None).unwrap();
+/// let tags: Vec<String> = tagger.tag(&["3.14", "running", "dog"]).into_iter().map(|(_, tag)| tag).collect();
+/// assert_eq!(tags, vec!["CD", "VBG", "NN"]);
+/// ```
+pub struct RegexpTagger {
+    patterns: Vec<(Regex, String)>,
+    backoff: Option<Box<dyn SequentialTagger>>,
+}
+
+impl RegexpTagger {
+    /// patterns: (regular expression, tag) pairs, tried in order
+    pub fn new(patterns: &[(&str, &str)], backoff: Option<Box<dyn SequentialTagger>>) -> anyhow::Result<Self> {
+        let mut compiled = Vec::with_capacity(patterns.len());
+        for (pattern, tag) in patterns {
+            compiled.push((Regex::new(&format!("^(?:{})", pattern))?, tag.to_string()));
+        }
+        Ok(Self { patterns: compiled, backoff })
+    }
+}
+
+impl SequentialTagger for RegexpTagger {
+    fn choose_tag(&self, tokens: &[&str], index: usize, _history: &[String]) -> Option<String> {
+        self.patterns.iter()
+            .find(|(regex, _)| regex.is_match(tokens[index]))
+            .map(|(_, tag)| tag.clone())
+    }
+
+    fn backoff(&self) -> Option<&dyn SequentialTagger> {
+        self.backoff.as_deref()
+    }
+}
+
+/// Context of a token for an n-gram tagger: the tags of the n-1 preceding tokens, and the token itself
+type Context = (Vec<String>, String);
+
+/// Assigns the tag that was seen most often in training, for the token and the tags of the N-1 tokens before it
+pub struct NgramTagger<const N: usize> {
+    context_to_tag: HashMap<Context, String>,
+    backoff: Option<Box<dyn SequentialTagger>>,
+}
+
+/// Assigns the most frequent tag for the token
+pub type UnigramTagger = NgramTagger<1>;
+/// Assigns the most frequent tag for the token, following the tag of the previous token
+pub type BigramTagger = NgramTagger<2>;
+/// Assigns the most frequent tag for the token, following the tags of the previous two tokens
+pub type TrigramTagger = NgramTagger<3>;
+
+impl<const N: usize> NgramTagger<N> {
+    /// Trains the tagger on a tagged corpus.
+    ///
+    /// Contexts are only kept if the most frequent tag for them was seen more than `cutoff` times.
+    /// If several tags are equally frequent, the alphabetically first one is taken.
+    /// Contexts for which the backoff tagger always gives the correct tag are left out, to keep the model small.
+    pub fn train(corpus: &[TaggedSentence], backoff: Option<Box<dyn SequentialTagger>>, cutoff: usize) -> Self {
+        assert!(N > 0, "n must be > 0");
+        let mut counts: HashMap<Context, HashMap<&str, usize>> = HashMap::new();
+        let mut useful_contexts = HashSet::new();
+
+        for sentence in corpus {
+            let tokens: Vec<&str> = sentence.iter().map(|(token, _)| *token).collect();
+            let tags: Vec<&str> = sentence.iter().map(|(_, tag)| *tag).collect();
+            let history: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+
+            // every window of n tags ends with the tag of a token, and starts with the tags in its context
+            let windows = ngrams(pad_sequence_left(tags.iter(), &START, N), N);
+            for (index, window) in windows.enumerate() {
+                let window: Vec<String> = window.map(|tag| tag.to_string()).collect();
+                let context = (window[..N - 1].to_vec(), tokens[index].to_string());
+                *counts.entry(context.clone()).or_default().entry(tags[index]).or_insert(0) += 1;
+
+                let backoff_tag = backoff.as_ref().and_then(|backoff| backoff.tag_one(&tokens, index, &history[..index]));
+                if backoff_tag.as_deref() != Some(tags[index]) {
+                    useful_contexts.insert(context);
+                }
+            }
+        }
+
+        let mut context_to_tag = HashMap::new();
+        for context in useful_contexts {
+            let (best_tag, hits) = counts[&context].iter()
+                .max_by_key(|(tag, count)| (**count, std::cmp::Reverse(**tag)))
+                .map(|(tag, count)| (tag.to_string(), *count))
+                .unwrap();
+            if hits > cutoff {
+                context_to_tag.insert(context, best_tag);
+            }
+        }
+
+        Self { context_to_tag, backoff }
+    }
+
+    /// number of contexts in the model
+    pub fn size(&self) -> usize {
+        self.context_to_tag.len()
+    }
+
+    fn context(tokens: &[&str], index: usize, history: &[String]) -> Context {
+        let tags = (index as isize - (N as isize - 1)..index as isize)
+            .map(|i| if i < 0 { START.to_string() } else { history[i as usize].clone() })
+            .collect();
+        (tags, tokens[index].to_string())
+    }
+}
+
+impl<const N: usize> SequentialTagger for NgramTagger<N> {
+    fn choose_tag(&self, tokens: &[&str], index: usize, history: &[String]) -> Option<String> {
+        self.context_to_tag.get(&Self::context(tokens, index, history)).cloned()
+    }
+
+    fn backoff(&self) -> Option<&dyn SequentialTagger> {
+        self.backoff.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> Vec<TaggedSentence<'static>> {
+        vec![
+            vec![("I", "PRP"), ("can", "MD"), ("fish", "VB")],
+            vec![("a", "DT"), ("can", "NN"), ("of", "IN"), ("fish", "NN")],
+            vec![("they", "PRP"), ("can", "MD"), ("swim", "VB")],
+        ]
+    }
+
+    fn tags(tagged: Vec<(&str, String)>) -> Vec<String> {
+        tagged.into_iter().map(|(_, tag)| tag).collect()
+    }
+
+    #[test]
+    fn test_default_tagger() {
+        let tagger = DefaultTagger::new("NN");
+        assert_eq!(tags(tagger.tag(&["a", "b"])), vec!["NN", "NN"]);
+    }
+
+    #[test]
+    fn test_unigram_takes_most_frequent_tag() {
+        let tagger = UnigramTagger::train(&corpus(), None, 0);
+        assert_eq!(tags(tagger.tag(&["a", "can", "of", "fish", "unseen"])), vec!["DT", "MD", "IN", "NN", ""]);
+    }
+
+    #[test]
+    fn test_bigram_uses_previous_tag() {
+        let unigram = UnigramTagger::train(&corpus(), Some(Box::new(DefaultTagger::new("NN"))), 0);
+        let bigram = BigramTagger::train(&corpus(), Some(Box::new(unigram)), 0);
+        assert_eq!(tags(bigram.tag(&["a", "can", "of", "fish"])), vec!["DT", "NN", "IN", "NN"]);
+        assert_eq!(tags(bigram.tag(&["they", "can", "fish"])), vec!["PRP", "MD", "VB"]);
+    }
+
+    #[test]
+    fn test_contexts_handled_by_backoff_are_left_out() {
+        let unigram = UnigramTagger::train(&corpus(), None, 0);
+        let without_backoff = BigramTagger::train(&corpus(), None, 0);
+        let with_backoff = BigramTagger::train(&corpus(), Some(Box::new(unigram)), 0);
+        assert_eq!(without_backoff.size(), 9);
+        assert_eq!(with_backoff.size(), 2);
+    }
+
+    #[test]
+    fn test_cutoff() {
+        let tagger = UnigramTagger::train(&corpus(), None, 1);
+        assert_eq!(tags(tagger.tag(&["can", "swim"])), vec!["MD", ""]);
+    }
+
+    #[test]
+    fn test_trigram() {
+        let tagger = TrigramTagger::train(&corpus(), None, 0);
+        assert_eq!(tags(tagger.tag(&["a", "can", "of"])), vec!["DT", "NN", "IN"]);
+        assert_eq!(tags(tagger.tag(&["of", "can"])), vec!["", ""]);
+    }
+
+    #[test]
+    fn test_regexp_tagger_backs_off() {
+        let tagger = RegexpTagger::new(&[(r"\d+$", "CD")], Some(Box::new(DefaultTagger::new("NN")))).unwrap();
+        assert_eq!(tags(tagger.tag(&["42", "a42"])), vec!["CD", "NN"]);
+    }
+}
@@ -23,4 +23,8 @@ fn collect<'a>(iter: &mut impl Iterator<Item=impl Iterator<Item=&'a &'a str>>) -
     let mut vec = Vec::new();
     vec.extend(iter);
     vec
-}
\ No newline at end of file
+}
+
+pub fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+    assert!((actual - expected).abs() < tolerance, "{} != {}", actual, expected);
+}
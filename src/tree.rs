@@ -0,0 +1,346 @@
+use std::fmt;
+
+use anyhow::anyhow;
+
+/// A tree with a label on every node, and leaves of type T
+///
+/// ```
+/// use rltk::tree::Tree;
+///
+/// let tree = Tree::from_bracketed("(S (NP (DT the) (NN dog)) (VP (VBD barked)))").unwrap();
+/// assert_eq!(tree.leaves(), vec!["the", "dog", "barked"]);
+/// assert_eq!(tree.height(), 4);
+/// assert_eq!(tree.to_string(), "(S (NP (DT the) (NN dog)) (VP (VBD barked)))");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tree<T> {
+    pub label: String,
+    pub children: Vec<Child<T>>,
+}
+
+/// A child of a node in a tree: a subtree or a leaf
+#[derive(Clone, Debug, PartialEq)]
+pub enum Child<T> {
+    Tree(Tree<T>),
+    Leaf(T),
+}
+
+/// How leaves are written in the bracketed notation of a tree
+pub trait Leaf {
+    fn fmt_leaf(&self, f: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+impl Leaf for String {
+    fn fmt_leaf(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl Leaf for &str {
+    fn fmt_leaf(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+/// (token, tag) pairs, the leaves of chunk trees, are written as token/tag
+impl Leaf for (&str, &str) {
+    fn fmt_leaf(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        write!(f, "{}/{}", self.0, self.1)
+    }
+}
+
+impl<T> Tree<T> {
+    pub fn new(label: &str, children: Vec<Child<T>>) -> Self {
+        Self { label: label.to_string(), children }
+    }
+
+    /// all leaves, from left to right
+    pub fn leaves(&self) -> Vec<&T> {
+        let mut leaves = Vec::new();
+        self.collect_leaves(&mut leaves);
+        leaves
+    }
+
+    fn collect_leaves<'a>(&'a self, leaves: &mut Vec<&'a T>) {
+        for child in &self.children {
+            match child {
+                Child::Tree(tree) => tree.collect_leaves(leaves),
+                Child::Leaf(leaf) => leaves.push(leaf),
+            }
+        }
+    }
+
+    /// all leaves with the label of their parent, which for a parse tree is their part of speech
+    pub fn pos(&self) -> Vec<(&T, &str)> {
+        let mut pos = Vec::new();
+        self.collect_pos(&mut pos);
+        pos
+    }
+
+    fn collect_pos<'a>(&'a self, pos: &mut Vec<(&'a T, &'a str)>) {
+        for child in &self.children {
+            match child {
+                Child::Tree(tree) => tree.collect_pos(pos),
+                Child::Leaf(leaf) => pos.push((leaf, &self.label)),
+            }
+        }
+    }
+
+    /// this tree and all of its subtrees for which the filter holds, in pre-order
+    pub fn subtrees(&self, filter: impl Fn(&Tree<T>) -> bool) -> Vec<&Tree<T>> {
+        let mut subtrees = Vec::new();
+        self.collect_subtrees(&filter, &mut subtrees);
+        subtrees
+    }
+
+    fn collect_subtrees<'a>(&'a self, filter: &impl Fn(&Tree<T>) -> bool, subtrees: &mut Vec<&'a Tree<T>>) {
+        if filter(self) {
+            subtrees.push(self);
+        }
+        for child in &self.children {
+            if let Child::Tree(tree) = child {
+                tree.collect_subtrees(filter, subtrees);
+            }
+        }
+    }
+
+    /// number of nodes on the longest path from the root to a leaf, counting the leaf.
+    /// A tree that only has leaves as children has height 2
+    pub fn height(&self) -> usize {
+        1 + self.children.iter()
+            .map(|child| match child {
+                Child::Tree(tree) => tree.height(),
+                Child::Leaf(_) => 1,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Tree<String> {
+    /// Parses a tree in the bracketed notation of the Penn Treebank, like `(S (NP (NNP John)) (VP (VBZ runs)))`.
+    /// A missing label, as in the outer brackets of `( (S ...) )`, becomes an empty label.
+    pub fn from_bracketed(text: &str) -> anyhow::Result<Self> {
+        let mut trees = Self::parse_all(text)?;
+        match trees.len() {
+            1 => Ok(trees.remove(0)),
+            n => Err(anyhow!("expected one tree, found {}", n)),
+        }
+    }
+
+    /// Parses all bracketed trees in a text, like a treebank file
+    pub fn parse_all(text: &str) -> anyhow::Result<Vec<Self>> {
+        let tokens = tokenize(text);
+        let mut trees = Vec::new();
+        let mut position = 0;
+        while position < tokens.len() {
+            trees.push(parse_tree(&tokens, &mut position)?);
+        }
+        Ok(trees)
+    }
+}
+
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        if c == '(' || c == ')' || c.is_whitespace() {
+            if let Some(s) = start.take() {
+                tokens.push(&text[s..index]);
+            }
+            if !c.is_whitespace() {
+                tokens.push(&text[index..index + 1]);
+            }
+        } else if start.is_none() {
+            start = Some(index);
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(&text[s..]);
+    }
+    tokens
+}
+
+fn parse_tree(tokens: &[&str], position: &mut usize) -> anyhow::Result<Tree<String>> {
+    if tokens.get(*position) != Some(&"(") {
+        return Err(anyhow!("expected '(' at token {}", position));
+    }
+    *position += 1;
+    let label = match tokens.get(*position) {
+        Some(&"(") | Some(&")") => "",
+        Some(label) => {
+            *position += 1;
+            label
+        }
+        None => return Err(anyhow!("unexpected end of tree")),
+    };
+    let mut children = Vec::new();
+    loop {
+        match tokens.get(*position) {
+            Some(&")") => {
+                *position += 1;
+                return Ok(Tree::new(label, children));
+            }
+            Some(&"(") => children.push(Child::Tree(parse_tree(tokens, position)?)),
+            Some(leaf) => {
+                children.push(Child::Leaf(leaf.to_string()));
+                *position += 1;
+            }
+            None => return Err(anyhow!("unexpected end of tree, missing ')'")),
+        }
+    }
+}
+
+impl<T: Leaf> Tree<T> {
+    /// Bracketed notation, on one line if it fits within the margin, otherwise with one child per line, indented
+    pub fn pformat(&self, margin: usize) -> String {
+        let mut text = String::new();
+        self.write_pformat(&mut text, margin, 0).expect("writing to a string does not fail");
+        text
+    }
+
+    fn write_pformat(&self, text: &mut String, margin: usize, indent: usize) -> fmt::Result {
+        let flat = self.to_string();
+        if flat.len() + indent < margin {
+            text.push_str(&flat);
+            return Ok(());
+        }
+        text.push('(');
+        text.push_str(&self.label);
+        for child in &self.children {
+            text.push('\n');
+            text.push_str(&" ".repeat(indent + 2));
+            match child {
+                Child::Tree(tree) => tree.write_pformat(text, margin, indent + 2)?,
+                Child::Leaf(leaf) => leaf.fmt_leaf(text)?,
+            }
+        }
+        text.push(')');
+        Ok(())
+    }
+
+    /// Draws the tree with ASCII lines, a node per line:
+    /// ```text
+    /// S
+    /// |-- NP
+    /// |   `-- John
+    /// `-- VP
+    ///     `-- runs
+    /// ```
+    pub fn to_ascii(&self) -> String {
+        let mut text = self.label.clone();
+        text.push('\n');
+        self.write_ascii_children(&mut text, "").expect("writing to a string does not fail");
+        text
+    }
+
+    fn write_ascii_children(&self, text: &mut String, prefix: &str) -> fmt::Result {
+        for (index, child) in self.children.iter().enumerate() {
+            let last = index + 1 == self.children.len();
+            text.push_str(prefix);
+            text.push_str(if last { "`-- " } else { "|-- " });
+            match child {
+                Child::Tree(tree) => {
+                    text.push_str(&tree.label);
+                    text.push('\n');
+                    let prefix = format!("{}{}", prefix, if last { "    " } else { "|   " });
+                    tree.write_ascii_children(text, &prefix)?;
+                }
+                Child::Leaf(leaf) => {
+                    leaf.fmt_leaf(text)?;
+                    text.push('\n');
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// bracketed notation on one line
+impl<T: Leaf> fmt::Display for Tree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}", self.label)?;
+        for child in &self.children {
+            f.write_str(" ")?;
+            match child {
+                Child::Tree(tree) => write!(f, "{}", tree)?,
+                Child::Leaf(leaf) => leaf.fmt_leaf(f)?,
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PENN: &str = "( (S (NP-SBJ (NNP Pierre) (NNP Vinken))
+        (VP (MD will) (VP (VB join) (NP (DT the) (NN board))))
+        (. .)))";
+
+    #[test]
+    fn test_parse() {
+        let tree = Tree::from_bracketed(PENN).unwrap();
+        assert_eq!(tree.label, "");
+        assert_eq!(tree.leaves(), vec!["Pierre", "Vinken", "will", "join", "the", "board", "."]);
+        assert_eq!(tree.height(), 7);
+        let pos: Vec<(&str, &str)> = tree.pos().into_iter().map(|(word, tag)| (word.as_str(), tag)).collect();
+        assert_eq!(pos[2], ("will", "MD"));
+        assert_eq!(pos[6], (".", "."));
+    }
+
+    #[test]
+    fn test_subtrees() {
+        let tree = Tree::from_bracketed(PENN).unwrap();
+        let noun_phrases: Vec<String> = tree.subtrees(|t| t.label.starts_with("NP")).iter().map(|t| t.to_string()).collect();
+        assert_eq!(noun_phrases, vec!["(NP-SBJ (NNP Pierre) (NNP Vinken))", "(NP (DT the) (NN board))"]);
+        assert_eq!(tree.subtrees(|_| true).len(), 13);
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let trees = Tree::parse_all("(A (B x)) (C y z)").unwrap();
+        assert_eq!(trees.len(), 2);
+        assert_eq!(trees[1].to_string(), "(C y z)");
+        assert!(Tree::from_bracketed("(A (B x)) (C y z)").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Tree::from_bracketed("(S (NP x)").is_err());
+        assert!(Tree::from_bracketed("S x)").is_err());
+        assert!(Tree::from_bracketed("").is_err());
+    }
+
+    #[test]
+    fn test_pformat() {
+        let tree = Tree::from_bracketed("(S (NP (DT the) (NN board)) (VP (VBD met)))").unwrap();
+        assert_eq!(tree.pformat(70), tree.to_string());
+        assert_eq!(tree.pformat(30), "(S\n  (NP (DT the) (NN board))\n  (VP (VBD met)))");
+    }
+
+    #[test]
+    fn test_ascii() {
+        let tree = Tree::from_bracketed("(S (NP (NNP John)) (VP (VBZ runs) (ADV fast)))").unwrap();
+        let expected = "\
+S
+|-- NP
+|   `-- NNP
+|       `-- John
+`-- VP
+    |-- VBZ
+    |   `-- runs
+    `-- ADV
+        `-- fast
+";
+        assert_eq!(tree.to_ascii(), expected);
+    }
+
+    #[test]
+    fn test_tuple_leaves() {
+        let tree = Tree::new("S", vec![Child::Tree(Tree::new("NP", vec![Child::Leaf(("the", "DT"))])), Child::Leaf(("ran", "VBD"))]);
+        assert_eq!(tree.to_string(), "(S (NP the/DT) ran/VBD)");
+        assert_eq!(tree.pos(), vec![(&("the", "DT"), "NP"), (&("ran", "VBD"), "S")]);
+    }
+}
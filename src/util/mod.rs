@@ -1,5 +1,6 @@
 pub(crate) mod padding;
 pub(crate) mod ngrams;
+pub(crate) mod random;
 
 use padding::Padder;
 
@@ -0,0 +1,57 @@
+/// Minimal xorshift pseudo random generator, for shuffling training data reproducibly.
+/// Not suitable for anything that needs real randomness.
+pub(crate) struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    pub(crate) fn new(seed: u64) -> Self {
+        // the state must never be zero
+        Self { state: seed.max(1) }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    /// a number in 0..bound
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Fisher-Yates shuffle
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shuffle_is_a_permutation() {
+        let mut items = [1, 2, 3, 4, 5, 6, 7, 8];
+        XorShift::new(42).shuffle(&mut items);
+        let mut sorted = items;
+        sorted.sort();
+        assert_eq!(sorted, [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_same_seed_same_order() {
+        let mut first = [1, 2, 3, 4, 5];
+        let mut second = [1, 2, 3, 4, 5];
+        XorShift::new(7).shuffle(&mut first);
+        XorShift::new(7).shuffle(&mut second);
+        assert_eq!(first, second);
+    }
+}